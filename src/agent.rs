@@ -1,16 +1,59 @@
+use crate::crossover;
+use crate::fitness::Fitness;
+use crate::mutation::{self, MutationConfig};
 use crate::nodes::eat_node::EatNode;
+use crate::nodes::plan::BrainPlan;
+use crate::nodes::vision_node::VisionNode;
+use crate::nodes::{food_gradient_node::FoodGradientNode, food_node::FoodNode};
+use crate::nodes::{forward_node::ForwardNode, turn_node::TurnNode};
+use crate::nodes::{latch_node::LatchNode, memory_node::MemoryNode};
 use crate::nodes::{move_node::MoveNode, node::Node, random_node::RandomNode, BaseNode};
+use crate::nodes::{oscillator_node::OscillatorNode, proprioception_node::ProprioceptionNode};
 use crate::util::color::Color;
+use crate::world::innovation::InnovationRegistry;
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
 
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, f32::consts::TAU, rc::Rc};
+
+pub const STARTING_ENERGY: f32 = 1.0; //energy every agent is born with
+pub const FOOD_ENERGY: f32 = 1.0; //energy gained for each unit of food eaten
+pub const METABOLISM_COST: f32 = 0.0002; //energy burned every frame just by being alive
+pub const MOVE_COST: f32 = 0.0005; //energy burned for each unit moved
+pub const MAX_TURN: f32 = 0.3; //most an agent can turn in one frame, in radians
+pub const REPRODUCTION_ENERGY: f32 = 2.0; //energy needed before an agent splits in two
+
+///which effector nodes brains are built with
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Locomotion {
+    Axis,    //MoveNodes move the agent along the world's x or y axis
+    Heading, //TurnNodes and ForwardNodes move the agent relative to its heading
+}
 
 pub struct Agent {
     pub brain: Vec<Rc<RefCell<Box<dyn BaseNode>>>>,
+    pub node_ids: Vec<u64>, //id of each node in the brain, handed out by the world's InnovationRegistry
+    pub plan: BrainPlan, //the brain compiled for evaluation, rebuilt by compile_brain whenever the brain changes
 
     //attributes that affect the way the agent interacts with environment
-    pub x: f32,       //how much we move in the x direction each frame
-    pub y: f32,       //how much we move in the y direction each frame
-    pub color: Color, //color of the agent
+    pub x: f32,                  //how much we move in the x direction each frame
+    pub y: f32,                  //how much we move in the y direction each frame
+    pub color: Color,            //color of the agent
+    pub energy: f32,             //stored energy, the agent dies when this reaches 0
+    pub heading: f32,            //direction the agent faces in radians
+    pub age: u32,                //frames the agent has been alive
+    pub food_eaten: f32,         //food eaten over the agent's life
+    pub distance_travelled: f32, //distance moved over the agent's life, including moves into walls
+    pub offspring: u32,          //children the agent has been a parent of
+    pub species: u64, //id of the species the agent was last assigned to, children start in it too
+    pub locomotion: Locomotion, //which effector nodes this agent's brain can grow
+
+    //where the agent is in the world's Lineage, the id and birth are set when the world records the birth
+    pub id: u64,
+    pub parent: Option<u64>, //id of the parent the brain structure came from, None for seeded agents
+    pub mate: Option<u64>,   //id of the other parent of a crossover
+    pub birth: u64,          //tick the agent was born on
+    pub death: Option<u64>,  //tick the agent died on
 }
 
 pub struct RenderableAgent {
@@ -19,15 +62,127 @@ pub struct RenderableAgent {
     pub color: Color, //color of the agent
 }
 
-//TODO: let mut rng = rand::thread_rng(); instead of rand::random()
 impl Agent {
-    pub fn new() -> Agent {
+    pub fn new(locomotion: Locomotion, rng: &mut dyn RngCore) -> Agent {
         Agent {
             brain: Vec::new(),
+            node_ids: Vec::new(),
+            plan: BrainPlan::default(),
             x: 0.0,
             y: 0.0,
-            color: Color::random(),
+            color: Color::random(rng),
+            energy: STARTING_ENERGY,
+            heading: rng.gen::<f32>() * TAU,
+            age: 0,
+            food_eaten: 0.0,
+            distance_travelled: 0.0,
+            offspring: 0,
+            species: 0,
+            locomotion,
+            id: 0,
+            parent: None,
+            mate: None,
+            birth: 0,
+            death: None,
+        }
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.energy <= 0.0
+    }
+
+    ///makes a child with half of the agent's energy, which the caller takes away from the agent
+    ///the child's brain is a mutated copy of this brain, or a mutated crossover with the mate's brain
+    pub fn reproduce(
+        &self,
+        mate: Option<&Agent>,
+        fitness: &dyn Fitness,
+        mutation: &MutationConfig,
+        innovations: &mut InnovationRegistry,
+        rng: &mut dyn RngCore,
+    ) -> Agent {
+        let mut child = self.offspring(mate, fitness, rng);
+        mutation::mutate(&mut child, mutation, innovations, rng);
+        child.compile_brain();
+
+        child
+    }
+
+    ///makes an unmutated child with half of the agent's energy, its brain still has to be compiled
+    pub fn offspring(
+        &self,
+        mate: Option<&Agent>,
+        fitness: &dyn Fitness,
+        rng: &mut dyn RngCore,
+    ) -> Agent {
+        let mut child = Agent {
+            brain: Vec::new(),
+            node_ids: Vec::new(),
+            plan: BrainPlan::default(),
+            x: self.x,
+            y: self.y,
+            color: self.color,
+            energy: self.energy / 2.0,
+            heading: self.heading,
+            age: 0,
+            food_eaten: 0.0,
+            distance_travelled: 0.0,
+            offspring: 0,
+            species: self.species,
+            locomotion: self.locomotion,
+            id: 0,
+            parent: Some(self.id),
+            mate: mate.map(|mate| mate.id),
+            birth: 0,
+            death: None,
+        };
+
+        //the fitter parent passes on its structure
+        match mate {
+            Some(mate) if fitness.score(mate) > fitness.score(self) => {
+                crossover::crossover(mate, self, &mut child, rng);
+                child.parent = Some(mate.id);
+                child.mate = Some(self.id);
+            }
+            Some(mate) => crossover::crossover(self, mate, &mut child, rng),
+            None => {
+                child.brain = self.copy_brain();
+                child.node_ids = self.node_ids.clone();
+            }
+        }
+
+        child
+    }
+
+    ///compiles the brain into the plan that is evaluated each frame, call this after changing the brain
+    pub fn compile_brain(&mut self) {
+        self.plan = BrainPlan::compile(&self.brain);
+    }
+
+    ///deep copies the brain, the edges are rebuilt between the new nodes so nothing is shared with this agent
+    fn copy_brain(&self) -> Vec<Rc<RefCell<Box<dyn BaseNode>>>> {
+        let brain: Vec<Rc<RefCell<Box<dyn BaseNode>>>> = self
+            .brain
+            .iter()
+            .map(|node| Rc::new(RefCell::new(node.borrow().clone_node())))
+            .collect();
+
+        for (i, node) in self.brain.iter().enumerate() {
+            let node = node.borrow();
+            let edges = node.get_inputs().iter().zip(node.get_weights());
+            for ((input, weight), innovation) in edges.zip(node.get_innovations()) {
+                let input = &brain[self.get_node_index(input)];
+                brain[i]
+                    .borrow_mut()
+                    .add_input(Rc::clone(input), *weight, *innovation);
+            }
+            for output in node.get_outputs() {
+                let output = &brain[self.get_node_index(output)];
+                brain[i].borrow_mut().add_output(Rc::clone(output));
+            }
         }
+
+        brain
     }
 
     pub fn renderable_clone(&self) -> RenderableAgent {
@@ -38,47 +193,208 @@ impl Agent {
         }
     }
 
-    pub fn add_random_node(&mut self) {
-        match rand::random::<usize>() % 4 {
-            0 => self
-                .brain
-                .push(Rc::new(RefCell::new(Box::new(RandomNode::new())))),
-            1 => self
-                .brain
-                .push(Rc::new(RefCell::new(Box::new(Node::new())))),
-            2 => self
-                .brain
-                .push(Rc::new(RefCell::new(Box::new(MoveNode::new())))),
-            3 => self
-                .brain
-                .push(Rc::new(RefCell::new(Box::new(EatNode::new())))),
-            _ => panic!("Random number generator failed"),
-        }
+    pub fn add_random_node(&mut self, innovations: &mut InnovationRegistry, rng: &mut dyn RngCore) {
+        let node = self.random_node(rng);
+        self.brain.push(Rc::new(RefCell::new(node)));
+        self.node_ids.push(innovations.node());
     }
 
-    pub fn connect_random_nodes(&mut self) {
-        let node1 = self.get_random_node();
-        let node2 = self.get_random_node();
+    ///adds a random node to a seeded brain, the k-th node of a type gets the same id in every seeded brain,
+    ///so seeds with similar brains share connection innovation numbers and land in the same species
+    pub fn add_random_seed_node(
+        &mut self,
+        innovations: &mut InnovationRegistry,
+        rng: &mut dyn RngCore,
+    ) {
+        let node = self.random_node(rng);
+        let name = node.name();
+        let k = self
+            .brain
+            .iter()
+            .filter(|other| other.borrow().name() == name)
+            .count();
+        self.brain.push(Rc::new(RefCell::new(node)));
+        self.node_ids.push(innovations.seed_node(name, k));
+    }
 
-        self.connect_nodes(node1, node2);
+    fn random_node(&self, rng: &mut dyn RngCore) -> Box<dyn BaseNode> {
+        match rng.gen_range(0..12) {
+            0 => Box::new(RandomNode::new()),
+            1 => Box::new(Node::new(rng)),
+            2 | 7 if self.locomotion == Locomotion::Axis => Box::new(MoveNode::new(rng)),
+            2 => Box::new(TurnNode::new(rng)),
+            7 => Box::new(ForwardNode::new(rng)),
+            8 => Box::new(ProprioceptionNode::new(rng)),
+            9 => Box::new(OscillatorNode::new(rng)),
+            10 => Box::new(LatchNode::new()),
+            11 => Box::new(MemoryNode::new(rng)),
+            3 => Box::new(EatNode::new(rng)),
+            4 => Box::new(FoodNode::new()),
+            5 => Box::new(FoodGradientNode::new(rng)),
+            6 => Box::new(VisionNode::new(rng)),
+            _ => panic!("Random number generator failed"),
+        }
     }
 
-    fn connect_nodes(
+    pub fn connect_random_nodes(
         &mut self,
-        node1: Rc<RefCell<Box<dyn BaseNode>>>,
-        node2: Rc<RefCell<Box<dyn BaseNode>>>,
+        innovations: &mut InnovationRegistry,
+        rng: &mut dyn RngCore,
     ) {
+        let node1 = self.get_random_node(rng);
+        //only nodes that read their inputs can be the end of a connection
+        let targets: Vec<usize> = (0..self.brain.len())
+            .filter(|i| self.brain[*i].borrow().accepts_inputs())
+            .collect();
+        if targets.is_empty() {
+            return;
+        }
+        let node2 = targets[rng.gen_range(0..targets.len())];
+
+        self.connect_nodes(node1, node2, innovations);
+    }
+
+    ///connects the node at index node1 into the node at index node2
+    fn connect_nodes(&mut self, node1: usize, node2: usize, innovations: &mut InnovationRegistry) {
         //Node can't be input to itself!!!
-        if Rc::ptr_eq(&node1, &node2) {
+        if node1 == node2 || !self.brain[node2].borrow().accepts_inputs() {
+            return;
+        }
+
+        //a connection can only exist once, otherwise its innovation number would be in the brain twice
+        let (input, output) = (&self.brain[node1], &self.brain[node2]);
+        if output
+            .borrow()
+            .get_inputs()
+            .iter()
+            .any(|existing| Rc::ptr_eq(existing, input))
+        {
             return;
         }
 
         //connect the nodes
-        node1.borrow_mut().add_output(Rc::clone(&node2));
-        node2.borrow_mut().add_input(Rc::clone(&node1));
+        let innovation = innovations.connection(self.node_ids[node1], self.node_ids[node2]);
+        input.borrow_mut().add_output(Rc::clone(output));
+        output
+            .borrow_mut()
+            .add_input(Rc::clone(input), 1.0, innovation);
+    }
+
+    ///removes a random node and every edge that touches it
+    pub fn remove_random_node(&mut self, rng: &mut dyn RngCore) {
+        if self.brain.is_empty() {
+            return;
+        }
+
+        let index = self.get_random_node(rng);
+        let removed = self.brain.remove(index);
+        self.node_ids.remove(index);
+        removed.borrow_mut().disconnect();
+
+        //scan the whole brain so edges to nodes that ignore inputs are cleaned up too
+        for node in &self.brain {
+            let mut node = node.borrow_mut();
+            while let Some(i) = node
+                .get_inputs()
+                .iter()
+                .position(|input| Rc::ptr_eq(input, &removed))
+            {
+                node.remove_input(i);
+            }
+            while node
+                .get_outputs()
+                .iter()
+                .any(|output| Rc::ptr_eq(output, &removed))
+            {
+                node.remove_output(&removed);
+            }
+        }
     }
 
-    fn get_random_node(&self) -> Rc<RefCell<Box<dyn BaseNode>>> {
-        Rc::clone(&self.brain[rand::random::<usize>() % self.brain.len()])
+    ///removes a random connection, the nodes on either end stay in the brain
+    pub fn remove_random_connection(&mut self, rng: &mut dyn RngCore) {
+        if let Some((node, index)) = self.get_random_connection(rng) {
+            let node = Rc::clone(&self.brain[node]);
+            let input = Rc::clone(&node.borrow().get_inputs()[index]);
+            node.borrow_mut().remove_input(index);
+            input.borrow_mut().remove_output(&node);
+        }
+    }
+
+    ///replaces a random connection with a hidden Node between its two ends
+    pub fn split_random_connection(
+        &mut self,
+        innovations: &mut InnovationRegistry,
+        rng: &mut dyn RngCore,
+    ) {
+        if let Some((node_index, index)) = self.get_random_connection(rng) {
+            let node = Rc::clone(&self.brain[node_index]);
+            let input = Rc::clone(&node.borrow().get_inputs()[index]);
+            let node_id = self.node_ids[node_index];
+            let input_id = self.node_ids[self.get_node_index(&input)];
+
+            //this connection was split before and then reconnected, the hidden node is already here
+            let hidden_id = innovations.split(node.borrow().get_innovations()[index]);
+            if self.node_ids.contains(&hidden_id) {
+                return;
+            }
+
+            let weight = node.borrow().get_weights()[index];
+            node.borrow_mut().remove_input(index);
+            input.borrow_mut().remove_output(&node);
+
+            //the edge into the hidden node has weight 1 and the edge out keeps the old weight
+            let hidden: Rc<RefCell<Box<dyn BaseNode>>> =
+                Rc::new(RefCell::new(Box::new(Node::new(rng))));
+            input.borrow_mut().add_output(Rc::clone(&hidden));
+            hidden.borrow_mut().add_input(
+                Rc::clone(&input),
+                1.0,
+                innovations.connection(input_id, hidden_id),
+            );
+            hidden.borrow_mut().add_output(Rc::clone(&node));
+            node.borrow_mut().add_input(
+                Rc::clone(&hidden),
+                weight,
+                innovations.connection(hidden_id, node_id),
+            );
+            self.brain.push(hidden);
+            self.node_ids.push(hidden_id);
+        }
+    }
+
+    ///picks a random edge, returns the brain index of the node it goes into and its index in that node's inputs
+    fn get_random_connection(&self, rng: &mut dyn RngCore) -> Option<(usize, usize)> {
+        let connections: Vec<(usize, usize)> = self
+            .brain
+            .iter()
+            .enumerate()
+            .flat_map(|(i, node)| (0..node.borrow().get_inputs().len()).map(move |j| (i, j)))
+            .collect();
+        if connections.is_empty() {
+            return None;
+        }
+
+        Some(connections[rng.gen_range(0..connections.len())])
+    }
+
+    fn get_random_node(&self, rng: &mut dyn RngCore) -> usize {
+        rng.gen_range(0..self.brain.len())
+    }
+
+    pub fn get_node_index(&self, node: &Rc<RefCell<Box<dyn BaseNode>>>) -> usize {
+        self.brain
+            .iter()
+            .position(|brain_node| Rc::ptr_eq(brain_node, node))
+            .expect("node is not part of this brain")
+    }
+}
+
+impl Drop for Agent {
+    fn drop(&mut self) {
+        //nodes hold Rcs to each other, so the brain would never be freed without breaking the cycles
+        for node in &self.brain {
+            node.borrow_mut().disconnect();
+        }
     }
 }
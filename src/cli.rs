@@ -0,0 +1,299 @@
+use crate::clock::{Clock, TickRate};
+use crate::config::Config;
+use crate::save;
+use crate::statistics::Statistics;
+use crate::world::{World, WorldControls};
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+pub const USAGE: &str = "usage:
+  evolution-sim [config options]               open the window
+  evolution-sim run [config options] [options] simulate with no window as fast as possible
+
+config options:
+  --config PATH      read settings from a TOML file, missing keys keep their defaults
+  --set KEY=VALUE    override one setting with the file's dotted key, like mutation.add_node_chance=0.2
+  --seed N           same as --set seed=N, a random seed is printed if none is set
+  --agents N         same as --set agents=N
+  --stats PATH       same as --set statistics.path=PATH, writes CSV or JSON Lines if PATH ends in .jsonl
+  --stats-every N    same as --set statistics.interval=N
+
+run options:
+  --ticks N                    frames to simulate (default 10000)
+  --report N                   print a line of stats every N frames, 0 for none (default 1000)
+  --rate N                     most ticks per second, inf for as fast as possible (default inf)
+  --load PATH                  resume from a world snapshot, which keeps its own settings except statistics
+  --save PATH                  write a world snapshot when the run ends
+  --newick PATH                write the family tree of every agent of the run in Newick format when the run ends
+  --lineage PATH               write every agent of the run and its parents as a JSON edge list when the run ends
+  --import-agent PATH          add an agent from a genome file to the world, can be given more than once
+  --export-agent ID|best PATH  write the genome of the agent with this id, or of the best agent of the run, when the run ends";
+
+///settings that still apply to a world loaded from a snapshot, everything else comes from the snapshot
+const LOADED_SETTINGS: [&str; 1] = ["statistics"];
+
+///what the program was asked to do from the command line
+pub enum Command {
+    Gui(Config),
+    Run(RunOptions),
+    Help,
+}
+
+pub struct RunOptions {
+    pub config: Config,
+    pub ticks: u64,
+    pub report: u64,
+    pub rate: TickRate,
+    pub load: Option<PathBuf>,
+    pub save: Option<PathBuf>,
+    pub newick: Option<PathBuf>,
+    pub lineage: Option<PathBuf>,
+    pub import_agents: Vec<PathBuf>,
+    pub export_agent: Option<(ExportedAgent, PathBuf)>,
+}
+
+///which agent --export-agent writes
+pub enum ExportedAgent {
+    Id(u64),
+    Best, //the top of the hall of fame, counting the agents still alive
+}
+
+///reads the arguments after the program name
+pub fn parse(args: &[String]) -> Result<Command, String> {
+    let (headless, mut rest) = match args.split_first() {
+        Some((command, rest)) if command == "run" => (true, rest),
+        Some((command, _)) if command == "help" => return Ok(Command::Help),
+        Some((command, _)) if !command.starts_with('-') => {
+            return Err(format!("unknown command {}", command))
+        }
+        _ => (false, args),
+    };
+
+    let mut config_path = None;
+    let mut overrides = Vec::new();
+    let (mut ticks, mut report, mut load, mut save) = (10000, 1000, None, None);
+    let (mut newick, mut lineage) = (None, None);
+    let (mut import_agents, mut export_agent) = (Vec::new(), None);
+    let mut rate = TickRate::Unlimited;
+    while let Some((flag, after)) = rest.split_first() {
+        if flag == "-h" || flag == "--help" {
+            return Ok(Command::Help);
+        }
+
+        let Some((value, after)) = after.split_first() else {
+            return Err(format!("{} needs a value", flag));
+        };
+        match flag.as_str() {
+            "--config" => config_path = Some(PathBuf::from(value)),
+            "--set" => overrides.push(value.clone()),
+            "--seed" => overrides.push(format!("seed={}", number::<u64>(flag, value)?)),
+            "--agents" => overrides.push(format!("agents={}", number::<usize>(flag, value)?)),
+            "--stats" => overrides.push(format!(
+                "statistics.path={}",
+                toml::Value::String(value.clone())
+            )),
+            "--stats-every" => overrides.push(format!(
+                "statistics.interval={}",
+                number::<u64>(flag, value)?
+            )),
+            "--ticks" if headless => ticks = number(flag, value)?,
+            "--report" if headless => report = number(flag, value)?,
+            "--rate" if headless => rate = TickRate::per_second(rate_value(flag, value)?),
+            "--load" if headless => load = Some(PathBuf::from(value)),
+            "--save" if headless => save = Some(PathBuf::from(value)),
+            "--newick" if headless => newick = Some(PathBuf::from(value)),
+            "--lineage" if headless => lineage = Some(PathBuf::from(value)),
+            "--import-agent" if headless => import_agents.push(PathBuf::from(value)),
+            "--export-agent" if headless => {
+                let Some((path, after)) = after.split_first() else {
+                    return Err(format!("{} needs an agent and a path", flag));
+                };
+                let agent = match value.as_str() {
+                    "best" => ExportedAgent::Best,
+                    id => ExportedAgent::Id(number(flag, id)?),
+                };
+                export_agent = Some((agent, PathBuf::from(path)));
+                rest = after;
+                continue;
+            }
+            _ => return Err(format!("unknown option {}", flag)),
+        }
+        rest = after;
+    }
+
+    //a loaded world keeps its own settings, so say which ones won't do anything instead of silently dropping them
+    if let Some(path) = &load {
+        let ignored: Vec<&str> = overrides
+            .iter()
+            .map(|text| {
+                text.split_once('=')
+                    .map_or(text.as_str(), |(key, _)| key.trim())
+            })
+            .filter(|key| {
+                let section = key.split('.').next().unwrap_or(key);
+                !LOADED_SETTINGS.contains(&section)
+            })
+            .collect();
+        if !ignored.is_empty() {
+            eprintln!(
+                "warning: {} ignored, the world loaded from {} keeps its own settings",
+                ignored.join(", "),
+                path.display()
+            );
+        }
+        if config_path.is_some() {
+            eprintln!(
+                "warning: only the {} settings of the config file apply to a loaded world",
+                LOADED_SETTINGS.join(" and ")
+            );
+        }
+    }
+
+    let config =
+        Config::load(config_path.as_deref(), &overrides).map_err(|error| match &config_path {
+            Some(path) => format!("{}: {}", path.display(), error),
+            None => error.to_string(),
+        })?;
+    if headless {
+        Ok(Command::Run(RunOptions {
+            config,
+            ticks,
+            report,
+            rate,
+            load,
+            save,
+            newick,
+            lineage,
+            import_agents,
+            export_agent,
+        }))
+    } else {
+        Ok(Command::Gui(config))
+    }
+}
+
+fn number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{} expects a whole number, got {}", flag, value))
+}
+
+fn rate_value(flag: &str, value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(rate) if rate > 0.0 => Ok(rate),
+        _ => Err(format!(
+            "{} expects a number of ticks per second above 0 or inf, got {}",
+            flag, value
+        )),
+    }
+}
+
+///simulates the world on this thread with no window, returns an error message if the run couldn't finish
+pub fn run(options: &RunOptions) -> Result<(), String> {
+    //the controls are only read by the window, World::step ignores them
+    let controls = Arc::new(Mutex::new(WorldControls::new()));
+    let mut world = World::new(controls, &options.config);
+    match &options.load {
+        Some(path) => world
+            .load(path)
+            .map_err(|error| format!("could not load {}: {}", path.display(), error))?,
+        None => world.add_n_agents(options.config.agents),
+    }
+    for path in &options.import_agents {
+        let agent = save::load_agent(path, &mut world.innovations)
+            .map_err(|error| format!("could not import {}: {}", path.display(), error))?;
+        world.add_agent(agent);
+    }
+    println!("seed {} tick {}", world.seed, world.tick);
+
+    let stats_error = |error| format!("could not write statistics: {}", error);
+    let mut statistics =
+        Statistics::create(&options.config.statistics, &world).map_err(stats_error)?;
+
+    let start = Instant::now();
+    let mut clock = Clock::new();
+    while clock.ticks < options.ticks {
+        clock.wait(options.rate);
+        world.step();
+        clock.tick();
+        if let Some(statistics) = &mut statistics {
+            statistics.record(&world).map_err(stats_error)?;
+        }
+        if options.report > 0 && world.tick.is_multiple_of(options.report) {
+            report(&world, &clock);
+        }
+    }
+
+    let elapsed = start.elapsed().as_secs_f64();
+    println!(
+        "ran {} ticks in {:.2}s ({:.0} ticks/s)",
+        options.ticks,
+        elapsed,
+        options.ticks as f64 / elapsed.max(f64::EPSILON)
+    );
+    if let Some(entry) = world.best_agents().entries.first() {
+        println!(
+            "best score {:.3} at tick {} in species {}",
+            entry.score, entry.tick, entry.species
+        );
+    }
+    let living = world.agents.iter().map(|agent| agent.id);
+    if let Some(ancestor) = world.lineage.common_ancestor(living) {
+        println!(
+            "every agent descends from agent {} born at tick {}, {} generations from its seeded ancestor",
+            ancestor,
+            world.lineage.get(ancestor).unwrap().birth,
+            world.lineage.depth(ancestor)
+        );
+    }
+
+    if let Some(path) = &options.save {
+        world
+            .save(path)
+            .map_err(|error| format!("could not save {}: {}", path.display(), error))?;
+        println!("saved to {}", path.display());
+    }
+    if let Some((agent, path)) = &options.export_agent {
+        let genome = match agent {
+            ExportedAgent::Id(id) => world
+                .genome(*id)
+                .ok_or(format!("agent {} is not alive or in the hall of fame", id))?,
+            ExportedAgent::Best => match world.best_agents().entries.first() {
+                Some(entry) => entry.genome.clone(),
+                None => return Err("there is no best agent, the world is empty".to_string()),
+            },
+        };
+        save::save_agent(&genome, path)
+            .map_err(|error| format!("could not export {}: {}", path.display(), error))?;
+        println!("exported agent to {}", path.display());
+    }
+    if let Some(path) = &options.newick {
+        world
+            .lineage
+            .write_newick(path)
+            .map_err(|error| format!("could not write {}: {}", path.display(), error))?;
+    }
+    if let Some(path) = &options.lineage {
+        world
+            .lineage
+            .write_edge_list(path)
+            .map_err(|error| format!("could not write {}: {}", path.display(), error))?;
+    }
+    Ok(())
+}
+
+fn report(world: &World, clock: &Clock) {
+    let energy: f32 = world.agents.iter().map(|agent| agent.energy).sum();
+    println!(
+        "tick {} generation {} agents {} species {} mean energy {:.3} ({:.0} ticks/s)",
+        world.tick,
+        world.generation,
+        world.agents.len(),
+        world.species.len(),
+        energy / world.agents.len().max(1) as f32,
+        clock.measured_rate()
+    );
+}
@@ -0,0 +1,101 @@
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+const MEASURE_WINDOW: Duration = Duration::from_secs(1); //the measured rate is updated this often
+const MAX_LAG: Duration = Duration::from_millis(250); //most a limited clock tries to catch up on
+
+///how often the clock lets the world step
+#[derive(Copy, Clone, PartialEq)]
+pub enum TickRate {
+    Limited(f64), //ticks per second
+    Unlimited,    //as fast as the world can step
+}
+
+impl TickRate {
+    ///an infinite rate is unlimited
+    pub fn per_second(rate: f64) -> TickRate {
+        if rate.is_finite() {
+            TickRate::Limited(rate)
+        } else {
+            TickRate::Unlimited
+        }
+    }
+}
+
+///fixed timestep clock for the loop that steps the world, all of the waiting happens here so World::step never sleeps
+pub struct Clock {
+    pub ticks: u64, //ticks the clock has counted
+    rate: TickRate,
+    next_tick: Instant, //when the next tick is due at a limited rate
+    window_start: Instant,
+    window_ticks: u64,
+    measured: f64, //ticks per second over the last whole window
+}
+
+impl Clock {
+    pub fn new() -> Clock {
+        let now = Instant::now();
+        Clock {
+            ticks: 0,
+            rate: TickRate::Unlimited,
+            next_tick: now,
+            window_start: now,
+            window_ticks: 0,
+            measured: 0.0,
+        }
+    }
+
+    ///sleeps until the next tick is due at this rate, call it before every step
+    pub fn wait(&mut self, rate: TickRate) {
+        //a new rate starts a new schedule, otherwise going from slow to fast would wait out the slow tick
+        if rate != self.rate {
+            self.rate = rate;
+            self.next_tick = Instant::now();
+        }
+
+        let TickRate::Limited(per_second) = rate else {
+            return;
+        };
+        let now = Instant::now();
+        if self.next_tick > now {
+            thread::sleep(self.next_tick - now);
+        }
+
+        //late ticks are caught up by not sleeping, but a clock that fell far behind starts over instead
+        //of rushing through everything it missed, like after the world was slow to step for a while
+        let now = Instant::now();
+        if now.saturating_duration_since(self.next_tick) > MAX_LAG {
+            self.next_tick = now;
+        }
+        self.next_tick += Duration::from_secs_f64(1.0 / per_second);
+    }
+
+    ///counts a tick, call it after every step
+    pub fn tick(&mut self) {
+        self.ticks += 1;
+        self.window_ticks += 1;
+
+        let elapsed = self.window_start.elapsed();
+        if elapsed >= MEASURE_WINDOW {
+            self.measured = self.window_ticks as f64 / elapsed.as_secs_f64();
+            self.window_start = Instant::now();
+            self.window_ticks = 0;
+        }
+    }
+
+    ///call while the world isn't stepping, so the time spent paused doesn't count against the rate
+    pub fn pause(&mut self) {
+        let now = Instant::now();
+        self.next_tick = now;
+        self.window_start = now;
+        self.window_ticks = 0;
+        self.measured = 0.0;
+    }
+
+    ///ticks per second actually reached
+    pub fn measured_rate(&self) -> f64 {
+        self.measured
+    }
+}
@@ -0,0 +1,455 @@
+use crate::agent::Locomotion;
+use crate::clock::TickRate;
+use crate::fitness::FitnessConfig;
+use crate::mutation::{MutationConfig, ParameterMutation};
+use crate::statistics::StatisticsConfig;
+use crate::world::food::FoodGrowth;
+use crate::world::generation::{Evolution, GenerationConfig, Selection};
+use crate::world::geometry::Boundary;
+use crate::world::species::SpeciationConfig;
+use crate::world::GameSpeed;
+use serde::{Deserialize, Serialize};
+use std::{fmt, fs, io, path::Path};
+
+///every setting of a simulation, read from a TOML file where any missing key keeps its default
+///
+///```toml
+///seed = 7
+///agents = 500
+///
+///[world]
+///width = 40
+///boundary = "Wrap"
+///
+///[mutation]
+///add_node_chance = 0.2
+///```
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default = "Config::new", deny_unknown_fields)]
+pub struct Config {
+    pub seed: Option<u64>, //a random seed is picked if this is not set
+    pub agents: usize,     //agents the world starts with
+    pub locomotion: Locomotion,
+    pub evolution: Evolution,
+    pub hall_of_fame: usize,    //best agents remembered over the run
+    pub fitness: FitnessConfig, //how agents are scored for selection and the hall of fame
+    pub world: WorldConfig,
+    pub brain: BrainConfig,
+    pub speed: SpeedConfig,
+    pub food: FoodGrowth,
+    pub mutation: MutationConfig,
+    pub speciation: SpeciationConfig,
+    pub statistics: StatisticsConfig,
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize)]
+#[serde(default = "WorldConfig::new", deny_unknown_fields)]
+pub struct WorldConfig {
+    pub width: u32,  //cells across, the food grid has one cell per unit
+    pub height: u32, //cells down
+    pub boundary: Boundary,
+    pub eat_rate: f32, //most food an EatNode can eat in one frame
+}
+
+///size of the random brains the first agents are made with
+#[derive(Copy, Clone, Serialize, Deserialize)]
+#[serde(default = "BrainConfig::new", deny_unknown_fields)]
+pub struct BrainConfig {
+    pub nodes: usize,
+    pub connections: usize, //random connections tried, a connection that already exists is skipped
+}
+
+///ticks per second the window runs at for each GameSpeed, inf for as fast as possible
+#[derive(Copy, Clone, Serialize, Deserialize)]
+#[serde(default = "SpeedConfig::new", deny_unknown_fields)]
+pub struct SpeedConfig {
+    pub slow: f64,
+    pub medium: f64,
+    pub fast: f64,
+}
+
+pub enum ConfigError {
+    Io(io::Error),
+    Parse(toml::de::Error), //not valid TOML, an unknown key or a value of the wrong type
+    Override(String),       //a --set argument that isn't key=value
+    Invalid(String),        //a value that parses but can't be used
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io(error) => write!(f, "{}", error),
+            ConfigError::Parse(error) => write!(f, "invalid config: {}", error),
+            ConfigError::Override(text) => {
+                write!(f, "invalid override {}, expected key=value", text)
+            }
+            ConfigError::Invalid(reason) => write!(f, "invalid config: {}", reason),
+        }
+    }
+}
+
+impl From<io::Error> for ConfigError {
+    fn from(error: io::Error) -> ConfigError {
+        ConfigError::Io(error)
+    }
+}
+
+impl From<toml::de::Error> for ConfigError {
+    fn from(error: toml::de::Error) -> ConfigError {
+        ConfigError::Parse(error)
+    }
+}
+
+impl Config {
+    pub fn new() -> Config {
+        Config {
+            seed: None,
+            agents: 100,
+            locomotion: Locomotion::Heading,
+            evolution: Evolution::SteadyState,
+            hall_of_fame: 10,
+            fitness: FitnessConfig::FoodEaten,
+            world: WorldConfig::new(),
+            brain: BrainConfig::new(),
+            speed: SpeedConfig::new(),
+            food: FoodGrowth::new(),
+            mutation: MutationConfig::new(),
+            speciation: SpeciationConfig::new(),
+            statistics: StatisticsConfig::new(),
+        }
+    }
+
+    ///reads the config file if there is one, then applies each key=value override on top of it
+    ///overrides use the file's dotted keys and TOML values, like mutation.add_node_chance=0.2 or world.boundary="Wrap"
+    pub fn load(path: Option<&Path>, overrides: &[String]) -> Result<Config, ConfigError> {
+        let mut table = match path {
+            Some(path) => fs::read_to_string(path)?.parse::<toml::Table>()?,
+            None => toml::Table::new(),
+        };
+        for text in overrides {
+            set(&mut table, text)?;
+        }
+
+        let config: Config = table.try_into()?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    ///checks the values serde can't, like chances outside [0, 1]
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut errors = Vec::new();
+        let mut check = |ok: bool, message: &str| {
+            if !ok {
+                errors.push(message.to_string());
+            }
+        };
+        let chance = |value: f32| (0.0..=1.0).contains(&value);
+
+        check(self.world.width > 0, "world.width must be at least 1");
+        check(self.world.height > 0, "world.height must be at least 1");
+        check(
+            self.world.eat_rate >= 0.0,
+            "world.eat_rate can't be negative",
+        );
+        check(self.brain.nodes > 0, "brain.nodes must be at least 1");
+        for (name, rate) in [
+            ("slow", self.speed.slow),
+            ("medium", self.speed.medium),
+            ("fast", self.speed.fast),
+        ] {
+            check(rate > 0.0, &format!("speed.{} must be above 0", name));
+        }
+
+        check(
+            self.food.regrowth_rate >= 0.0,
+            "food.regrowth_rate can't be negative",
+        );
+        check(
+            chance(self.food.diffusion_rate),
+            "food.diffusion_rate must be in [0, 1]",
+        );
+        check(self.food.seed >= 0.0, "food.seed can't be negative");
+
+        let mutation = &self.mutation;
+        for (name, value) in [
+            ("add_node_chance", mutation.add_node_chance),
+            ("remove_node_chance", mutation.remove_node_chance),
+            ("add_connection_chance", mutation.add_connection_chance),
+            (
+                "remove_connection_chance",
+                mutation.remove_connection_chance,
+            ),
+            ("split_connection_chance", mutation.split_connection_chance),
+            ("crossover_chance", mutation.crossover_chance),
+            ("activation_chance", mutation.activation_chance),
+        ] {
+            check(
+                chance(value),
+                &format!("mutation.{} must be in [0, 1]", name),
+            );
+        }
+        for (name, parameter) in [("weights", &mutation.weights), ("bias", &mutation.bias)] {
+            check_parameter(name, parameter, &mut check);
+        }
+        for (name, value) in [
+            ("color_drift", mutation.color_drift),
+            ("angle_drift", mutation.angle_drift),
+            ("frequency_drift", mutation.frequency_drift),
+            ("retention_drift", mutation.retention_drift),
+        ] {
+            check(
+                value >= 0.0,
+                &format!("mutation.{} can't be negative", name),
+            );
+        }
+
+        check(
+            self.speciation.threshold > 0.0,
+            "speciation.threshold must be above 0",
+        );
+        check(
+            self.speciation.interval > 0,
+            "speciation.interval must be at least 1",
+        );
+
+        check(
+            self.statistics.interval > 0,
+            "statistics.interval must be at least 1",
+        );
+
+        check_fitness(&self.fitness, &mut check);
+
+        if let Evolution::Generational(generation) = &self.evolution {
+            check_generation(generation, &mut check);
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::Invalid(errors.join(", ")))
+        }
+    }
+}
+
+fn check_parameter(name: &str, parameter: &ParameterMutation, check: &mut impl FnMut(bool, &str)) {
+    let chance = |value: f32| (0.0..=1.0).contains(&value);
+    for (field, value) in [
+        ("perturb_chance", parameter.perturb_chance),
+        ("reset_chance", parameter.reset_chance),
+        ("flip_chance", parameter.flip_chance),
+    ] {
+        check(
+            chance(value),
+            &format!("mutation.{}.{} must be in [0, 1]", name, field),
+        );
+    }
+    for (field, value) in [
+        ("perturb_size", parameter.perturb_size),
+        ("reset_range", parameter.reset_range),
+    ] {
+        check(
+            value >= 0.0,
+            &format!("mutation.{}.{} can't be negative", name, field),
+        );
+    }
+}
+
+fn check_fitness(fitness: &FitnessConfig, check: &mut impl FnMut(bool, &str)) {
+    let FitnessConfig::Weighted(parts) = fitness else {
+        return;
+    };
+    check(
+        !parts.is_empty(),
+        "fitness.Weighted needs at least one fitness",
+    );
+    for part in parts {
+        check(
+            part.weight.is_finite(),
+            "fitness.Weighted weights must be finite",
+        );
+        check_fitness(&part.fitness, check);
+    }
+}
+
+fn check_generation(generation: &GenerationConfig, check: &mut impl FnMut(bool, &str)) {
+    check(
+        generation.ticks > 0,
+        "evolution.Generational.ticks must be at least 1",
+    );
+    check(
+        generation.population > 0,
+        "evolution.Generational.population must be at least 1",
+    );
+    check(
+        generation.elitism <= generation.population,
+        "evolution.Generational.elitism can't be more than the population",
+    );
+    match generation.selection {
+        Selection::Tournament(size) => check(size > 0, "tournament size must be at least 1"),
+        Selection::Truncation(fraction) => check(
+            fraction > 0.0 && fraction <= 1.0,
+            "truncation fraction must be in (0, 1]",
+        ),
+        Selection::Roulette | Selection::Rank => {}
+    }
+}
+
+///sets one dotted key in the table, making any tables on the way that don't exist yet
+fn set(table: &mut toml::Table, text: &str) -> Result<(), ConfigError> {
+    let Some((key, value)) = text.split_once('=') else {
+        return Err(ConfigError::Override(text.to_string()));
+    };
+    let key = key.trim();
+    let value = value.trim();
+
+    //parsed as a one line document so the value follows the same rules as in a file
+    //a bare word that isn't valid TOML is taken as a string, so world.boundary=Wrap works without quotes
+    let value = match format!("value = {}", value).parse::<toml::Table>() {
+        Ok(mut parsed) => parsed.remove("value").unwrap(),
+        Err(_) => toml::Value::String(value.to_string()),
+    };
+
+    let mut parts: Vec<&str> = key.split('.').collect();
+    let last = parts.pop().unwrap();
+    if last.is_empty() || parts.iter().any(|part| part.is_empty()) {
+        return Err(ConfigError::Override(text.to_string()));
+    }
+
+    let mut current = table;
+    for part in parts {
+        let entry = current
+            .entry(part)
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+        current = match entry {
+            toml::Value::Table(inner) => inner,
+            _ => {
+                return Err(ConfigError::Invalid(format!(
+                    "{} is not a table, so {} can't be set",
+                    part, key
+                )))
+            }
+        };
+    }
+    current.insert(last.to_string(), value);
+    Ok(())
+}
+
+impl WorldConfig {
+    pub fn new() -> WorldConfig {
+        WorldConfig {
+            width: 25,
+            height: 25,
+            boundary: Boundary::Clamp,
+            eat_rate: 0.001,
+        }
+    }
+}
+
+impl BrainConfig {
+    pub fn new() -> BrainConfig {
+        BrainConfig {
+            nodes: 15,
+            connections: 25,
+        }
+    }
+}
+
+impl SpeedConfig {
+    pub fn new() -> SpeedConfig {
+        SpeedConfig {
+            slow: 2.5,
+            medium: 30.0,
+            fast: f64::INFINITY,
+        }
+    }
+
+    pub fn rate(&self, speed: GameSpeed) -> TickRate {
+        TickRate::per_second(match speed {
+            GameSpeed::Slow => self.slow,
+            GameSpeed::Medium => self.medium,
+            GameSpeed::Fast => self.fast,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(overrides: &[&str]) -> Result<Config, ConfigError> {
+        let overrides: Vec<String> = overrides.iter().map(|text| text.to_string()).collect();
+        Config::load(None, &overrides)
+    }
+
+    fn error(overrides: &[&str]) -> String {
+        match load(overrides) {
+            Ok(_) => panic!("{:?} should not be a valid config", overrides),
+            Err(error) => error.to_string(),
+        }
+    }
+
+    #[test]
+    fn defaults_are_valid() {
+        assert!(Config::new().validate().is_ok());
+    }
+
+    #[test]
+    fn overrides_set_nested_keys() {
+        let Ok(config) = load(&[
+            "seed=7",
+            "world.boundary=Wrap",
+            "mutation.weights.perturb_size=0.5",
+        ]) else {
+            panic!("the overrides should be valid");
+        };
+        assert_eq!(config.seed, Some(7));
+        assert!(config.world.boundary == Boundary::Wrap);
+        assert_eq!(config.mutation.weights.perturb_size, 0.5);
+    }
+
+    #[test]
+    fn every_invalid_value_is_reported() {
+        assert_eq!(
+            error(&[
+                "world.width=0",
+                "mutation.add_node_chance=2",
+                "speed.fast=-1"
+            ]),
+            "invalid config: world.width must be at least 1, speed.fast must be above 0, \
+             mutation.add_node_chance must be in [0, 1]"
+        );
+    }
+
+    #[test]
+    fn unknown_keys_and_wrong_types_are_parse_errors() {
+        assert!(matches!(
+            load(&["world.size=3"]),
+            Err(ConfigError::Parse(_))
+        ));
+        assert!(matches!(
+            load(&["agents=\"many\""]),
+            Err(ConfigError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn overrides_need_a_key_and_value() {
+        assert_eq!(
+            error(&["agents"]),
+            "invalid override agents, expected key=value"
+        );
+        assert!(matches!(load(&["world.=3"]), Err(ConfigError::Override(_))));
+        assert!(matches!(
+            load(&["seed=1", "seed.x=3"]),
+            Err(ConfigError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn weighted_fitness_is_checked() {
+        assert_eq!(
+            error(&["fitness={ Weighted = [] }"]),
+            "invalid config: fitness.Weighted needs at least one fitness"
+        );
+    }
+}
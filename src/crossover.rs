@@ -0,0 +1,263 @@
+use crate::agent::Agent;
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+///one connection of a brain, described by node ids so it can be compared between brains
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ConnectionGene {
+    pub innovation: u64,
+    pub input: u64,  //id of the node the connection comes from
+    pub output: u64, //id of the node the connection goes into
+    pub weight: f32,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum Parent {
+    First,
+    Second,
+}
+
+///where a gene sits when two parents' genes are lined up by innovation number
+pub enum Alignment<'a> {
+    Matching(&'a ConnectionGene, &'a ConnectionGene), //both parents have the gene
+    Disjoint(Parent, &'a ConnectionGene), //only one parent has it, within the other parent's innovation numbers
+    Excess(Parent, &'a ConnectionGene), //only one parent has it, past the other parent's last innovation number
+}
+
+///every connection in the agent's brain, sorted by innovation number
+pub fn connection_genes(agent: &Agent) -> Vec<ConnectionGene> {
+    let mut genes = vec![];
+    for (node, id) in agent.brain.iter().zip(&agent.node_ids) {
+        let node = node.borrow();
+        let edges = node.get_inputs().iter().zip(node.get_weights());
+        for ((input, weight), innovation) in edges.zip(node.get_innovations()) {
+            genes.push(ConnectionGene {
+                innovation: *innovation,
+                input: agent.node_ids[agent.get_node_index(input)],
+                output: *id,
+                weight: *weight,
+            });
+        }
+    }
+    genes.sort_by_key(|gene| gene.innovation);
+    genes
+}
+
+///lines up two sorted lists of genes by innovation number
+pub fn align<'a>(first: &'a [ConnectionGene], second: &'a [ConnectionGene]) -> Vec<Alignment<'a>> {
+    let first_last = first.last().map(|gene| gene.innovation);
+    let second_last = second.last().map(|gene| gene.innovation);
+    let unmatched =
+        |parent: Parent, gene: &'a ConnectionGene, other_last: Option<u64>| match other_last {
+            Some(last) if gene.innovation < last => Alignment::Disjoint(parent, gene),
+            _ => Alignment::Excess(parent, gene),
+        };
+
+    let mut alignment = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < first.len() || j < second.len() {
+        match (first.get(i), second.get(j)) {
+            (Some(a), Some(b)) if a.innovation == b.innovation => {
+                alignment.push(Alignment::Matching(a, b));
+                i += 1;
+                j += 1;
+            }
+            (Some(a), b) if b.is_none_or(|b| a.innovation < b.innovation) => {
+                alignment.push(unmatched(Parent::First, a, second_last));
+                i += 1;
+            }
+            (_, Some(b)) => {
+                alignment.push(unmatched(Parent::Second, b, first_last));
+                j += 1;
+            }
+            _ => break,
+        }
+    }
+    alignment
+}
+
+///fills the child's empty brain from both parents, matching genes come from either parent at random
+///while disjoint and excess genes only come from the fitter parent, so the child has the fitter parent's structure
+pub fn crossover(fitter: &Agent, other: &Agent, child: &mut Agent, rng: &mut dyn RngCore) {
+    let fitter_genes = connection_genes(fitter);
+    let other_genes = connection_genes(other);
+
+    //nodes both parents have take their parameters from either parent at random
+    child.node_ids = fitter.node_ids.clone();
+    child.brain = fitter
+        .brain
+        .iter()
+        .zip(&fitter.node_ids)
+        .map(|(node, id)| {
+            let node = match other.node_ids.iter().position(|other_id| other_id == id) {
+                Some(i) if rng.gen::<bool>() => &other.brain[i],
+                _ => node,
+            };
+            Rc::new(RefCell::new(node.borrow().clone_node()))
+        })
+        .collect();
+
+    let index: HashMap<u64, usize> = child
+        .node_ids
+        .iter()
+        .enumerate()
+        .map(|(i, id)| (*id, i))
+        .collect();
+    for alignment in align(&fitter_genes, &other_genes) {
+        //the ends of a gene always come from the fitter parent, only a matching gene's weight can come from the other
+        let (gene, weight) = match alignment {
+            Alignment::Matching(first, second) => {
+                if rng.gen::<bool>() {
+                    (first, first.weight)
+                } else {
+                    (first, second.weight)
+                }
+            }
+            Alignment::Disjoint(Parent::First, gene) | Alignment::Excess(Parent::First, gene) => {
+                (gene, gene.weight)
+            }
+            _ => continue,
+        };
+
+        let input = &child.brain[index[&gene.input]];
+        let output = &child.brain[index[&gene.output]];
+        input.borrow_mut().add_output(Rc::clone(output));
+        output
+            .borrow_mut()
+            .add_input(Rc::clone(input), weight, gene.innovation);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::Locomotion;
+    use crate::nodes::activation::Activation;
+    use crate::nodes::parameters::NodeParameters;
+    use rand::rngs::mock::StepRng;
+
+    fn gene(innovation: u64, input: u64, output: u64, weight: f32) -> ConnectionGene {
+        ConnectionGene {
+            innovation,
+            input,
+            output,
+            weight,
+        }
+    }
+
+    fn agent(node_ids: &[u64], genes: &[ConnectionGene]) -> Agent {
+        let mut agent = Agent::new(Locomotion::Heading, &mut StepRng::new(0, 0));
+        for id in node_ids {
+            let parameters = NodeParameters::Node {
+                bias: 0.0,
+                activation: Activation::Identity,
+            };
+            agent.brain.push(Rc::new(RefCell::new(parameters.build())));
+            agent.node_ids.push(*id);
+        }
+        for gene in genes {
+            let input = &agent.brain[node_ids.iter().position(|id| *id == gene.input).unwrap()];
+            let output = &agent.brain[node_ids.iter().position(|id| *id == gene.output).unwrap()];
+            input.borrow_mut().add_output(Rc::clone(output));
+            output
+                .borrow_mut()
+                .add_input(Rc::clone(input), gene.weight, gene.innovation);
+        }
+        agent
+    }
+
+    //the fitter parent has innovations 1, 2, 3 and 5, the other parent 1, 2, 4, 6 and 7
+    fn fitter_genes() -> Vec<ConnectionGene> {
+        vec![
+            gene(1, 0, 1, 1.0),
+            gene(2, 0, 2, 1.0),
+            gene(3, 1, 2, 1.0),
+            gene(5, 0, 3, 1.0),
+        ]
+    }
+
+    fn other_genes() -> Vec<ConnectionGene> {
+        vec![
+            gene(1, 0, 1, -1.0),
+            gene(2, 0, 2, -1.0),
+            gene(4, 2, 1, -1.0),
+            gene(6, 0, 4, -1.0),
+            gene(7, 4, 2, -1.0),
+        ]
+    }
+
+    #[test]
+    fn genes_are_matching_disjoint_or_excess() {
+        let (fitter, other) = (fitter_genes(), other_genes());
+        let alignment: Vec<String> = align(&fitter, &other)
+            .iter()
+            .map(|alignment| match alignment {
+                Alignment::Matching(a, b) => format!("matching {} {}", a.innovation, b.innovation),
+                Alignment::Disjoint(Parent::First, gene) => {
+                    format!("disjoint first {}", gene.innovation)
+                }
+                Alignment::Disjoint(Parent::Second, gene) => {
+                    format!("disjoint second {}", gene.innovation)
+                }
+                Alignment::Excess(Parent::First, gene) => {
+                    format!("excess first {}", gene.innovation)
+                }
+                Alignment::Excess(Parent::Second, gene) => {
+                    format!("excess second {}", gene.innovation)
+                }
+            })
+            .collect();
+
+        assert_eq!(
+            alignment,
+            [
+                "matching 1 1",
+                "matching 2 2",
+                "disjoint first 3",
+                "disjoint second 4",
+                "disjoint first 5",
+                "excess second 6",
+                "excess second 7",
+            ]
+        );
+    }
+
+    #[test]
+    fn child_has_the_fitter_parents_structure() {
+        let fitter = agent(&[0, 1, 2, 3], &fitter_genes());
+        let other = agent(&[0, 1, 2, 4], &other_genes());
+        let mut child = Agent::new(Locomotion::Heading, &mut StepRng::new(0, 0));
+        crossover(&fitter, &other, &mut child, &mut StepRng::new(0, 1 << 63));
+
+        assert_eq!(child.node_ids, [0, 1, 2, 3]);
+        let genes = connection_genes(&child);
+        let innovations: Vec<u64> = genes.iter().map(|gene| gene.innovation).collect();
+        assert_eq!(innovations, [1, 2, 3, 5]);
+        for gene in &genes {
+            match gene.innovation {
+                //matching genes can come from either parent
+                1 | 2 => assert!(gene.weight == 1.0 || gene.weight == -1.0),
+                //disjoint and excess genes only come from the fitter parent
+                _ => assert_eq!(gene.weight, 1.0),
+            }
+        }
+    }
+
+    #[test]
+    fn matching_genes_keep_the_fitter_parents_ends() {
+        //both parents have innovation 1, but in the other parent it goes into a node the fitter parent doesn't have
+        let fitter = agent(&[0, 1], &[gene(1, 0, 1, 1.0)]);
+        let other = agent(&[0, 2], &[gene(1, 0, 2, -1.0)]);
+
+        //one rng always picks the fitter parent's weight and the other the other parent's
+        for rng in [StepRng::new(0, 0), StepRng::new(u64::MAX, 0)].iter_mut() {
+            let mut child = Agent::new(Locomotion::Heading, &mut StepRng::new(0, 0));
+            crossover(&fitter, &other, &mut child, rng);
+
+            let genes = connection_genes(&child);
+            assert_eq!(genes.len(), 1);
+            assert_eq!((genes[0].input, genes[0].output), (0, 1));
+        }
+    }
+}
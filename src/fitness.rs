@@ -0,0 +1,103 @@
+use crate::agent::Agent;
+use serde::{Deserialize, Serialize};
+
+///scores how good an agent is from what it did during its life, higher is better
+pub trait Fitness {
+    fn score(&self, agent: &Agent) -> f32;
+}
+
+///which fitness function the world scores agents with, read from the config
+///
+///```toml
+///fitness = "DistanceTravelled"
+///#or
+///fitness = { Weighted = [{ weight = 1.0, fitness = "FoodEaten" }, { weight = 0.01, fitness = "TicksSurvived" }] }
+///```
+#[derive(Clone, Serialize, Deserialize)]
+pub enum FitnessConfig {
+    FoodEaten,
+    DistanceTravelled,
+    TicksSurvived,
+    Offspring,
+    Weighted(Vec<WeightedFitness>),
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WeightedFitness {
+    pub weight: f32,
+    pub fitness: FitnessConfig,
+}
+
+impl FitnessConfig {
+    pub fn build(&self) -> Box<dyn Fitness> {
+        match self {
+            FitnessConfig::FoodEaten => Box::new(FoodEaten),
+            FitnessConfig::DistanceTravelled => Box::new(DistanceTravelled),
+            FitnessConfig::TicksSurvived => Box::new(TicksSurvived),
+            FitnessConfig::Offspring => Box::new(Offspring),
+            FitnessConfig::Weighted(parts) => Box::new(Weighted::new(
+                parts
+                    .iter()
+                    .map(|part| (part.weight, part.fitness.build()))
+                    .collect(),
+            )),
+        }
+    }
+}
+
+///food eaten by EatNodes
+pub struct FoodEaten;
+
+impl Fitness for FoodEaten {
+    fn score(&self, agent: &Agent) -> f32 {
+        agent.food_eaten
+    }
+}
+
+///distance moved by MoveNodes and ForwardNodes
+pub struct DistanceTravelled;
+
+impl Fitness for DistanceTravelled {
+    fn score(&self, agent: &Agent) -> f32 {
+        agent.distance_travelled
+    }
+}
+
+///frames the agent stayed alive
+pub struct TicksSurvived;
+
+impl Fitness for TicksSurvived {
+    fn score(&self, agent: &Agent) -> f32 {
+        agent.age as f32
+    }
+}
+
+///children the agent was a parent of
+pub struct Offspring;
+
+impl Fitness for Offspring {
+    fn score(&self, agent: &Agent) -> f32 {
+        agent.offspring as f32
+    }
+}
+
+///sum of other fitness functions, each multiplied by its weight
+pub struct Weighted {
+    pub parts: Vec<(f32, Box<dyn Fitness>)>,
+}
+
+impl Weighted {
+    pub fn new(parts: Vec<(f32, Box<dyn Fitness>)>) -> Weighted {
+        Weighted { parts }
+    }
+}
+
+impl Fitness for Weighted {
+    fn score(&self, agent: &Agent) -> f32 {
+        self.parts
+            .iter()
+            .map(|(weight, fitness)| weight * fitness.score(agent))
+            .sum()
+    }
+}
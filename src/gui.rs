@@ -9,6 +9,9 @@ use crate::world::RenderableWorld;
 
 pub mod game;
 pub mod menu;
+///a clickable area, the callback run when it's clicked and the action reported to the renderer
+pub type Button = (GUILocation, Box<dyn FnMut()>, ClickAction);
+
 #[derive(Copy, Clone)]
 pub struct GUILocation {
     x: u32,
@@ -17,6 +20,7 @@ pub struct GUILocation {
     height: u32,
 }
 pub trait GraphicsWindow<T: Renderer> {
+    #[allow(clippy::too_many_arguments)]
     fn draw(
         &mut self,
         context: &PossiblyCurrentContext,
@@ -40,7 +44,7 @@ pub fn button_centered<T: Renderer>(
     paint: &Paint,
 ) -> GUILocation {
     let size = window.inner_size();
-    let text_metrics = canvas.measure_text(0.0, 0.0, text, &paint);
+    let text_metrics = canvas.measure_text(0.0, 0.0, text, paint);
     let text_height = text_metrics.as_ref().unwrap().height();
     let text_width = text_metrics.as_ref().unwrap().width();
 
@@ -68,7 +72,7 @@ pub fn button_centered<T: Renderer>(
             (size.width as f32 - text_width) / 2.0,
             text_height + height + 4.0,
             text,
-            &paint,
+            paint,
         )
         .unwrap();
 
@@ -90,7 +94,7 @@ pub fn button_right<T: Renderer>(
     paint: &Paint,
 ) -> GUILocation {
     let size = window.inner_size();
-    let text_metrics = canvas.measure_text(0.0, 0.0, text, &paint);
+    let text_metrics = canvas.measure_text(0.0, 0.0, text, paint);
     let text_height = text_metrics.as_ref().unwrap().height();
     let text_width = text_metrics.as_ref().unwrap().width();
 
@@ -118,7 +122,7 @@ pub fn button_right<T: Renderer>(
             size.width as f32 - text_width - right_pad - 5.0,
             text_height + height + 4.0,
             text,
-            &paint,
+            paint,
         )
         .unwrap();
 
@@ -139,7 +143,7 @@ pub fn text_centered<T: Renderer>(
     paint: &Paint,
 ) -> GUILocation {
     let size = window.inner_size();
-    let text_metrics = canvas.measure_text(0.0, 0.0, text, &paint);
+    let text_metrics = canvas.measure_text(0.0, 0.0, text, paint);
     let text_height = text_metrics.as_ref().unwrap().height();
     let text_width = text_metrics.as_ref().unwrap().width();
     canvas
@@ -147,7 +151,7 @@ pub fn text_centered<T: Renderer>(
             (size.width as f32 - text_width) / 2.0,
             text_height + height,
             text,
-            &paint,
+            paint,
         )
         .unwrap();
 
@@ -160,7 +164,6 @@ pub fn text_centered<T: Renderer>(
 }
 
 ///returns height where it finsihed drawing to the canvas
-#[allow(dead_code)]
 pub fn text_right<T: Renderer>(
     text: &str,
     height: f32,
@@ -170,7 +173,7 @@ pub fn text_right<T: Renderer>(
     paint: &Paint,
 ) -> GUILocation {
     let size = window.inner_size();
-    let text_metrics = canvas.measure_text(0.0, 0.0, text, &paint);
+    let text_metrics = canvas.measure_text(0.0, 0.0, text, paint);
     let text_height = text_metrics.as_ref().unwrap().height();
     let text_width = text_metrics.as_ref().unwrap().width();
     canvas
@@ -178,7 +181,7 @@ pub fn text_right<T: Renderer>(
             size.width as f32 - text_width - right_pad,
             text_height + height,
             text,
-            &paint,
+            paint,
         )
         .unwrap();
 
@@ -199,11 +202,11 @@ pub fn text_left<T: Renderer>(
     canvas: &mut Canvas<T>,
     paint: &Paint,
 ) -> GUILocation {
-    let text_metrics = canvas.measure_text(0.0, 0.0, text, &paint);
+    let text_metrics = canvas.measure_text(0.0, 0.0, text, paint);
     let text_height = text_metrics.as_ref().unwrap().height();
     let text_width = text_metrics.as_ref().unwrap().width();
     canvas
-        .fill_text(left_pad, text_height + height, text, &paint)
+        .fill_text(left_pad, text_height + height, text, paint)
         .unwrap();
 
     GUILocation {
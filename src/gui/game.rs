@@ -9,12 +9,12 @@ use crate::gui;
 use crate::renderer::ClickAction;
 use crate::world::{GameSpeed, RenderableWorld};
 
-use super::{GUILocation, GraphicsWindow};
+use super::{Button, GUILocation, GraphicsWindow};
 
 const UNIT_SIZE: u32 = 15;
 const UNIT_SIZE_F: f32 = UNIT_SIZE as f32;
 pub struct Game {
-    buttons: Vec<(GUILocation, Box<dyn FnMut() -> ()>, ClickAction)>,
+    buttons: Vec<Button>,
 }
 
 impl Game {
@@ -22,12 +22,13 @@ impl Game {
         Game { buttons: vec![] }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn button_right<T: Renderer>(
         &mut self,
         text: &str,
         height: f32,
         right_pad: f32,
-        callback: Box<dyn FnMut() -> ()>,
+        callback: Box<dyn FnMut()>,
         click_action: ClickAction,
         canvas: &mut Canvas<T>,
         window: &Window,
@@ -77,8 +78,8 @@ impl<T: Renderer> GraphicsWindow<T> for Game {
             Some(world) => {
                 //render the food
                 // render the food
-                for i in 0..world.width {
-                    for j in 0..world.height {
+                for i in 0..world.geometry.width {
+                    for j in 0..world.geometry.height {
                         let food_amount = world.food[i as usize][j as usize];
                         canvas.clear_rect(
                             i * UNIT_SIZE + x_offset as u32,
@@ -122,7 +123,7 @@ impl<T: Renderer> GraphicsWindow<T> for Game {
                     );
                     gui_location
                 } else {
-                    let gui_location = self.button_right(
+                    self.button_right(
                         "Step",
                         5.0,
                         5.0,
@@ -131,11 +132,10 @@ impl<T: Renderer> GraphicsWindow<T> for Game {
                         canvas,
                         window,
                         &fill_paint,
-                    );
-                    gui_location
+                    )
                 };
 
-                self.button_right(
+                let gui_location = self.button_right(
                     if world.controls.lock().unwrap().paused {
                         "Play"
                     } else {
@@ -143,12 +143,44 @@ impl<T: Renderer> GraphicsWindow<T> for Game {
                     },
                     5.0,
                     (size.width - gui_location.x + 5) as f32,
-                    Box::new(|| (println!("play/pause"))),
+                    Box::new(|| println!("play/pause")),
                     ClickAction::PlayPause,
                     canvas,
                     window,
                     &fill_paint,
                 );
+
+                let gui_location = self.button_right(
+                    "Save",
+                    5.0,
+                    (size.width - gui_location.x + 5) as f32,
+                    Box::new(|| {}),
+                    ClickAction::Save,
+                    canvas,
+                    window,
+                    &fill_paint,
+                );
+
+                let gui_location = self.button_right(
+                    "Load",
+                    5.0,
+                    (size.width - gui_location.x + 5) as f32,
+                    Box::new(|| {}),
+                    ClickAction::Load,
+                    canvas,
+                    window,
+                    &fill_paint,
+                );
+
+                //ticks per second the game thread is reaching
+                gui::text_right(
+                    &format!("{:.1} ticks/s", world.controls.lock().unwrap().tick_rate),
+                    (gui_location.y + gui_location.height + 10) as f32,
+                    5.0,
+                    canvas,
+                    window,
+                    &fill_paint,
+                );
             }
             _ => {
                 //render start button gui
@@ -9,10 +9,10 @@ use crate::gui;
 use crate::renderer::ClickAction;
 use crate::world::RenderableWorld;
 
-use super::{GUILocation, GraphicsWindow};
+use super::{Button, GUILocation, GraphicsWindow};
 
 pub struct Menu {
-    buttons: Vec<(GUILocation, Box<dyn FnMut() -> ()>, ClickAction)>,
+    buttons: Vec<Button>,
 }
 
 impl Menu {
@@ -20,11 +20,12 @@ impl Menu {
         Menu { buttons: vec![] }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn button_centered<T: Renderer>(
         &mut self,
         text: &str,
         height: f32,
-        callback: Box<dyn FnMut() -> ()>,
+        callback: Box<dyn FnMut()>,
         click_action: ClickAction,
         canvas: &mut Canvas<T>,
         window: &Window,
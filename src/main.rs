@@ -1,26 +1,55 @@
 mod agent;
+mod cli;
+mod clock;
+mod config;
+mod crossover;
+mod fitness;
 mod gui;
+mod mutation;
 mod nodes;
 mod renderer;
+mod save;
+mod statistics;
 mod util;
 mod world;
 
 use agent::Agent;
+use cli::Command;
+use clock::Clock;
+use config::Config;
 use renderer::GraphicsRenderer;
+use statistics::Statistics;
 use winit::dpi::PhysicalPosition;
 use world::{World, WorldControls};
 
 use femtovg::renderer::OpenGl;
 use femtovg::Canvas;
 use glutin::prelude::*;
-use std::cell::RefCell;
-use std::rc::Rc;
+use std::path::Path;
 use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
 use winit::event::{ElementState, Event, MouseButton, WindowEvent};
 use winit::event_loop::{ControlFlow, EventLoop};
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match cli::parse(&args) {
+        Ok(Command::Gui(config)) => gui(config),
+        Ok(Command::Run(options)) => {
+            if let Err(error) = cli::run(&options) {
+                eprintln!("{}", error);
+                std::process::exit(1);
+            }
+        }
+        Ok(Command::Help) => println!("{}", cli::USAGE),
+        Err(error) => {
+            eprintln!("{}\n\n{}", error, cli::USAGE);
+            std::process::exit(2);
+        }
+    }
+}
+
+fn gui(config: Config) {
     //channel used for sending the world to the rendering thread
     let (send, recv) = mpsc::channel();
 
@@ -29,22 +58,81 @@ fn main() {
     //game logic thread
     thread::spawn(move || {
         let world_controls_clone_2 = Arc::clone(&world_controls_clone);
-        let world = Rc::new(RefCell::new(World::new(world_controls_clone_2)));
-        world.borrow_mut().add_n_agents(100);
+        let mut world = World::new(world_controls_clone_2, &config);
+        //printed so an interesting run can be repeated
+        println!("seed {}", world.seed);
+        world.add_n_agents(config.agents);
 
         const FRAME_RATE: u32 = 60;
         let frame_duration = Duration::from_secs(1) / FRAME_RATE;
         let mut last_frame_time = Instant::now();
 
         //wait for game to start
-        while !(*world_controls_clone.lock().unwrap()).started {
+        while !world_controls_clone.lock().unwrap().started {
             thread::sleep(Duration::from_millis(12));
         }
 
+        //where the save and load buttons write and read the world
+        const SNAPSHOT_PATH: &str = "snapshot.json";
+
+        let mut statistics = match Statistics::create(&config.statistics, &world) {
+            Ok(statistics) => statistics,
+            Err(error) => {
+                eprintln!("could not write statistics: {error}");
+                None
+            }
+        };
+
+        let mut clock = Clock::new();
         loop {
-            World::simulate_frame(Rc::clone(&world));
+            //done between frames so a snapshot never holds a half simulated frame
+            let (save, load, paused, step, speed) = {
+                let mut controls = world_controls_clone.lock().unwrap();
+                let requests = (
+                    controls.save,
+                    controls.load,
+                    controls.paused,
+                    controls.step,
+                    controls.speed,
+                );
+                controls.save = false;
+                controls.load = false;
+                controls.step = false;
+                requests
+            };
+            if save {
+                if let Err(error) = world.save(Path::new(SNAPSHOT_PATH)) {
+                    eprintln!("could not save the world: {error}");
+                }
+            }
+            if load {
+                match world.load(Path::new(SNAPSHOT_PATH)) {
+                    Ok(()) => send.send(world.renderable_clone()).unwrap(),
+                    Err(error) => eprintln!("could not load the world: {error}"),
+                }
+            }
+
+            if paused && !step {
+                clock.pause();
+                thread::sleep(Duration::from_millis(12));
+            } else {
+                //stepping one frame at a time doesn't wait for the clock
+                if !step {
+                    clock.wait(config.speed.rate(speed));
+                }
+                world.step();
+                clock.tick();
+
+                //a statistics file that can't be written is given up on so the simulation keeps going
+                if let Some(Err(error)) = statistics.as_mut().map(|stats| stats.record(&world)) {
+                    eprintln!("could not write statistics: {error}");
+                    statistics = None;
+                }
+            }
+            world_controls_clone.lock().unwrap().tick_rate = clock.measured_rate();
+
             if last_frame_time.elapsed() >= frame_duration {
-                send.send(World::renderable_clone(&world.borrow())).unwrap();
+                send.send(world.renderable_clone()).unwrap();
                 last_frame_time = Instant::now();
             }
         }
@@ -77,8 +165,8 @@ fn main() {
         }
 
         //close window on exit
-        match event {
-            Event::WindowEvent { event, .. } => match event {
+        if let Event::WindowEvent { event, .. } = event {
+            match event {
                 WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
                 WindowEvent::MouseInput { button, state, .. } => match state {
                     ElementState::Pressed => {
@@ -109,8 +197,7 @@ fn main() {
                     last_position = position;
                 }
                 _ => {}
-            },
-            _ => {}
+            }
         }
     });
 }
@@ -0,0 +1,129 @@
+use crate::agent::Agent;
+use crate::world::innovation::InnovationRegistry;
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+
+///how each weight or bias of a child's brain is changed, every operator has its own chance
+#[derive(Copy, Clone, Serialize, Deserialize)]
+#[serde(default = "ParameterMutation::new", deny_unknown_fields)]
+pub struct ParameterMutation {
+    pub perturb_chance: f32, //chance the value gets gaussian noise added to it
+    pub perturb_size: f32,   //standard deviation of that noise
+    pub reset_chance: f32,   //chance the value is replaced by a new random value
+    pub reset_range: f32,    //new random values are uniform in [-reset_range, reset_range]
+    pub flip_chance: f32,    //chance the value flips its sign
+}
+
+impl ParameterMutation {
+    pub fn new() -> ParameterMutation {
+        ParameterMutation {
+            perturb_chance: 0.2,
+            perturb_size: 0.2,
+            reset_chance: 0.02,
+            reset_range: 2.0,
+            flip_chance: 0.01,
+        }
+    }
+
+    ///runs each operator on the value in turn, returns the mutated value
+    pub fn apply(&self, value: f32, rng: &mut dyn RngCore) -> f32 {
+        let mut value = value;
+        if rng.gen::<f32>() < self.perturb_chance {
+            value += gaussian(rng) * self.perturb_size;
+        }
+        if rng.gen::<f32>() < self.reset_chance {
+            value = (rng.gen::<f32>() * 2.0 - 1.0) * self.reset_range;
+        }
+        if rng.gen::<f32>() < self.flip_chance {
+            value = -value;
+        }
+        value
+    }
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize)]
+#[serde(default = "MutationConfig::new", deny_unknown_fields)]
+pub struct MutationConfig {
+    pub add_node_chance: f32,          //chance a child gets a new random node
+    pub remove_node_chance: f32,       //chance a child loses a random node and its edges
+    pub add_connection_chance: f32,    //chance a child gets a new random connection
+    pub remove_connection_chance: f32, //chance a child loses a random connection
+    pub split_connection_chance: f32,  //chance a connection gets a hidden node in its middle
+    pub crossover_chance: f32,         //chance a child gets a crossover of its parent and a mate
+    pub activation_chance: f32,        //chance each node gets a new random activation function
+    pub weights: ParameterMutation,    //how connection weights change
+    pub bias: ParameterMutation,       //how node biases change
+    pub color_drift: f32,              //max amount each color channel can change
+    pub angle_drift: f32,              //max amount in radians a vision node's angle can change
+    pub frequency_drift: f32,          //max fraction an oscillator node's frequency can change by
+    pub retention_drift: f32,          //max amount a memory node's retention can change
+}
+
+impl MutationConfig {
+    pub fn new() -> MutationConfig {
+        MutationConfig {
+            add_node_chance: 0.1,
+            remove_node_chance: 0.05,
+            add_connection_chance: 0.2,
+            remove_connection_chance: 0.1,
+            split_connection_chance: 0.05,
+            crossover_chance: 0.25,
+            activation_chance: 0.02,
+            weights: ParameterMutation::new(),
+            bias: ParameterMutation::new(),
+            color_drift: 0.05,
+            angle_drift: 0.2,
+            frequency_drift: 0.1,
+            retention_drift: 0.05,
+        }
+    }
+}
+
+///mutates a newly born agent in place
+pub fn mutate(
+    agent: &mut Agent,
+    config: &MutationConfig,
+    innovations: &mut InnovationRegistry,
+    rng: &mut dyn RngCore,
+) {
+    if rng.gen::<f32>() < config.add_node_chance {
+        agent.add_random_node(innovations, rng);
+    }
+
+    if rng.gen::<f32>() < config.remove_node_chance {
+        agent.remove_random_node(rng);
+    }
+
+    if !agent.brain.is_empty() && rng.gen::<f32>() < config.add_connection_chance {
+        agent.connect_random_nodes(innovations, rng);
+    }
+
+    if rng.gen::<f32>() < config.remove_connection_chance {
+        agent.remove_random_connection(rng);
+    }
+
+    if rng.gen::<f32>() < config.split_connection_chance {
+        agent.split_random_connection(innovations, rng);
+    }
+
+    for node in &agent.brain {
+        let mut node = node.borrow_mut();
+        for weight in node.get_weights_mut() {
+            *weight = config.weights.apply(*weight, rng);
+        }
+        let bias = config.bias.apply(node.get_bias(), rng);
+        node.set_bias(bias);
+
+        node.mutate(config, rng);
+    }
+
+    agent.color = agent.color.drift(config.color_drift, rng);
+}
+
+///standard normal random number, made with the Box-Muller transform
+fn gaussian(rng: &mut dyn RngCore) -> f32 {
+    let u1 = 1.0 - rng.gen::<f32>(); //in (0, 1] so the log is finite
+    let u2 = rng.gen::<f32>();
+    (-2.0 * u1.ln()).sqrt() * (TAU * u2).cos()
+}
@@ -1,15 +1,59 @@
+pub mod activation;
 pub mod eat_node;
+pub mod food_gradient_node;
+pub mod food_node;
+pub mod forward_node;
+pub mod latch_node;
+pub mod memory_node;
 pub mod move_node;
 pub mod node;
+pub mod oscillator_node;
+pub mod parameters;
+pub mod plan;
+pub mod proprioception_node;
 pub mod random_node;
+pub mod turn_node;
+pub mod vision_node;
 
 use super::World;
-use crate::agent::Agent;
+use crate::mutation::MutationConfig;
+use parameters::NodeParameters;
+use rand::RngCore;
 use std::{cell::RefCell, rc::Rc};
+
+///Nodes and their edges make up an agent's brain, they are compiled into a BrainPlan to be evaluated.
 pub trait BaseNode {
-    fn calculate_output(&mut self, agent: Rc<RefCell<Agent>>, world: Rc<RefCell<World>>);
-    fn add_input(&mut self, node: Rc<RefCell<Box<dyn BaseNode>>>);
+    ///input is the weighted sum of this node's inputs plus its bias, agent is the index of the agent in world.agents
+    fn calculate_output(&mut self, input: f32, agent: usize, world: &mut World) -> f32;
+    ///innovation is the historical number of the connection, handed out by the world's InnovationRegistry
+    fn add_input(&mut self, node: Rc<RefCell<Box<dyn BaseNode>>>, weight: f32, innovation: u64);
     fn add_output(&mut self, node: Rc<RefCell<Box<dyn BaseNode>>>);
-    fn get_output(&self) -> Option<f32>;
-    fn reset_output(&mut self);
+    fn remove_input(&mut self, index: usize); //removes the input at index along with its weight and innovation
+    fn remove_output(&mut self, node: &Rc<RefCell<Box<dyn BaseNode>>>); //removes one output edge to node
+    fn get_inputs(&self) -> &[Rc<RefCell<Box<dyn BaseNode>>>];
+    fn get_weights(&self) -> &[f32];
+    fn get_weights_mut(&mut self) -> &mut [f32];
+    fn get_innovations(&self) -> &[u64];
+    fn get_bias(&self) -> f32;
+    fn set_bias(&mut self, bias: f32);
+    fn get_outputs(&self) -> &[Rc<RefCell<Box<dyn BaseNode>>>];
+    fn disconnect(&mut self); //removes all inputs and outputs
+    fn clone_node(&self) -> Box<dyn BaseNode>; //copies the node's parameters but none of its edges
+    fn name(&self) -> &'static str; //name of the node's type
+    fn get_parameters(&self) -> NodeParameters; //the node's type and parameters, used to save it
+    fn set_parameters(&mut self, parameters: &NodeParameters); //does nothing if parameters are for another type
+
+    ///false for nodes that ignore add_input, like sensors, connections are never made into them
+    fn accepts_inputs(&self) -> bool {
+        true
+    }
+
+    ///state the node keeps between frames, saved in world snapshots
+    fn get_state(&self) -> f32 {
+        0.0
+    }
+    fn set_state(&mut self, _state: f32) {}
+
+    ///nodes with evolvable parameters change them here when a child is born
+    fn mutate(&mut self, _config: &MutationConfig, _rng: &mut dyn RngCore) {}
 }
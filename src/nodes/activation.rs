@@ -0,0 +1,64 @@
+use crate::mutation::MutationConfig;
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+
+///activation function a node applies to the weighted sum of its inputs
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Activation {
+    Tanh,
+    Sigmoid,
+    Relu,
+    Step,
+    Sine,
+    Gaussian,
+    Abs,
+    Identity,
+}
+
+impl Activation {
+    pub fn random(rng: &mut dyn RngCore) -> Activation {
+        Activation::from_index(rng.gen_range(0..8))
+    }
+
+    fn from_index(index: usize) -> Activation {
+        match index {
+            0 => Activation::Tanh,
+            1 => Activation::Sigmoid,
+            2 => Activation::Relu,
+            3 => Activation::Step,
+            4 => Activation::Sine,
+            5 => Activation::Gaussian,
+            6 => Activation::Abs,
+            7 => Activation::Identity,
+            _ => panic!("Activation index out of range"),
+        }
+    }
+
+    pub fn apply(&self, x: f32) -> f32 {
+        match self {
+            Activation::Tanh => x.tanh(),
+            Activation::Sigmoid => 1.0 / (1.0 + (-x).exp()),
+            Activation::Relu => x.max(0.0),
+            Activation::Step => {
+                if x > 0.0 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            Activation::Sine => x.sin(),
+            Activation::Gaussian => (-x * x).exp(),
+            Activation::Abs => x.abs(),
+            Activation::Identity => x,
+        }
+    }
+
+    ///has a chance of swapping the activation for a random one
+    pub fn mutate(self, config: &MutationConfig, rng: &mut dyn RngCore) -> Activation {
+        if rng.gen::<f32>() < config.activation_chance {
+            Activation::from_index(rng.gen_range(0..8))
+        } else {
+            self
+        }
+    }
+}
@@ -1,78 +1,143 @@
-use super::{Agent, BaseNode, World};
+use super::{activation::Activation, parameters::NodeParameters, BaseNode, World};
+use crate::agent::FOOD_ENERGY;
+use crate::mutation::MutationConfig;
+use rand::RngCore;
 use std::{cell::RefCell, rc::Rc};
 
 pub struct EatNode {
     // Define the properties of the node here
     inputs: Vec<Rc<RefCell<Box<dyn BaseNode>>>>, //all nodes that give input to this node
     weights: Vec<f32>,                           //weights for each input
+    innovations: Vec<u64>,                       //innovation number of each input connection
     bias: f32,                                   //bias for the node
+    activation: Activation, //activation function applied to the weighted inputs
 
     outputs: Vec<Rc<RefCell<Box<dyn BaseNode>>>>, //all node this node gives output to
-    output: Option<f32>, //this is the value all nodes in the outputs array use, if the Option is None this Node has not been calculated yet
 }
 
 impl EatNode {
-    pub fn new() -> EatNode {
+    pub fn new(rng: &mut dyn RngCore) -> EatNode {
         EatNode {
             inputs: Vec::new(),
             weights: Vec::new(),
+            innovations: Vec::new(),
             outputs: Vec::new(),
             bias: 0.0,
-            output: None,
+            activation: Activation::random(rng),
         }
     }
 }
 
 impl BaseNode for EatNode {
-    fn calculate_output(&mut self, agent: Rc<RefCell<Agent>>, world: Rc<RefCell<World>>) {
-        //calculate output of all input nodes first
-        let mut output: f32 = 0.0;
-
-        assert_eq!(self.inputs.len(), self.weights.len());
-        self.output.replace(0.0); //DONT DELETE. This line is needed to prevent error, otherwise we get in an infinite loop of caluclate_output
-        for (i, input) in self.inputs.iter().enumerate() {
-            if input.borrow().get_output().is_none() {
-                unsafe {
-                    let input_mut = &mut *input.as_ptr();
-                    input_mut.calculate_output(Rc::clone(&agent), Rc::clone(&world));
-                }
-            }
-            output += input.borrow().get_output().unwrap() * self.weights[i];
-        }
-
-        //add bias to output
-        output += self.bias;
-
-        //TODO: research what activation functions I should use for this project
-        //apply activation function on output
-        output = output.tanh();
+    fn calculate_output(&mut self, input: f32, agent: usize, world: &mut World) -> f32 {
+        //apply activation function on the weighted inputs, effectors can't go past full strength
+        let output = self.activation.apply(input).clamp(-1.0, 1.0);
 
         //eat output amount of food
-        //TODO:
-        let new_food = world.borrow().food[agent.borrow().x.min(99.0) as usize]
-            [agent.borrow().y.min(64.0) as usize]
-            - (output + 1.0) / 2.0 / 1000.0;
-        unsafe { &mut *world.as_ptr() }.food[agent.borrow().x.min(99.0) as usize]
-            [agent.borrow().y.min(64.0) as usize] = new_food.max(0.0);
+        let (i, j) = world
+            .geometry
+            .cell(world.agents[agent].x, world.agents[agent].y);
+        let eaten = ((output + 1.0) / 2.0 * world.eat_rate).min(world.food[i][j]);
+        world.food[i][j] -= eaten;
 
-        //set the output
-        self.output = Some(output);
+        //food that was eaten turns into energy
+        world.agents[agent].energy += eaten * FOOD_ENERGY;
+        world.agents[agent].food_eaten += eaten;
+
+        output
     }
 
-    fn add_input(&mut self, node: Rc<RefCell<Box<dyn BaseNode>>>) {
+    fn add_input(&mut self, node: Rc<RefCell<Box<dyn BaseNode>>>, weight: f32, innovation: u64) {
         self.inputs.push(Rc::clone(&node));
-        self.weights.push(1.0);
+        self.weights.push(weight);
+        self.innovations.push(innovation);
     }
 
     fn add_output(&mut self, node: Rc<RefCell<Box<dyn BaseNode>>>) {
         self.outputs.push(Rc::clone(&node));
     }
 
-    fn get_output(&self) -> Option<f32> {
-        self.output
+    fn remove_input(&mut self, index: usize) {
+        self.inputs.remove(index);
+        self.weights.remove(index);
+        self.innovations.remove(index);
+    }
+
+    fn remove_output(&mut self, node: &Rc<RefCell<Box<dyn BaseNode>>>) {
+        if let Some(i) = self
+            .outputs
+            .iter()
+            .position(|output| Rc::ptr_eq(output, node))
+        {
+            self.outputs.remove(i);
+        }
+    }
+
+    fn get_inputs(&self) -> &[Rc<RefCell<Box<dyn BaseNode>>>] {
+        &self.inputs
+    }
+
+    fn get_weights(&self) -> &[f32] {
+        &self.weights
+    }
+
+    fn get_weights_mut(&mut self) -> &mut [f32] {
+        &mut self.weights
+    }
+
+    fn get_innovations(&self) -> &[u64] {
+        &self.innovations
+    }
+
+    fn get_bias(&self) -> f32 {
+        self.bias
+    }
+
+    fn set_bias(&mut self, bias: f32) {
+        self.bias = bias;
+    }
+
+    fn get_outputs(&self) -> &[Rc<RefCell<Box<dyn BaseNode>>>] {
+        &self.outputs
+    }
+
+    fn disconnect(&mut self) {
+        self.inputs.clear();
+        self.weights.clear();
+        self.innovations.clear();
+        self.outputs.clear();
+    }
+
+    fn clone_node(&self) -> Box<dyn BaseNode> {
+        Box::new(EatNode {
+            inputs: Vec::new(),
+            weights: Vec::new(),
+            innovations: Vec::new(),
+            outputs: Vec::new(),
+            bias: self.bias,
+            activation: self.activation,
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "EatNode"
+    }
+
+    fn get_parameters(&self) -> NodeParameters {
+        NodeParameters::EatNode {
+            bias: self.bias,
+            activation: self.activation,
+        }
+    }
+
+    fn set_parameters(&mut self, parameters: &NodeParameters) {
+        if let NodeParameters::EatNode { bias, activation } = *parameters {
+            self.bias = bias;
+            self.activation = activation;
+        }
     }
 
-    fn reset_output(&mut self) {
-        self.output = None;
+    fn mutate(&mut self, config: &MutationConfig, rng: &mut dyn RngCore) {
+        self.activation = self.activation.mutate(config, rng);
     }
 }
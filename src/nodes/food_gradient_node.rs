@@ -0,0 +1,126 @@
+use super::{parameters::NodeParameters, BaseNode, World};
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::{cell::RefCell, rc::Rc};
+
+#[derive(Copy, Clone, Serialize, Deserialize)]
+pub enum GradientDirection {
+    X,
+    Y,
+}
+pub struct FoodGradientNode {
+    // Define the properties of the node here
+    gradient_direction: GradientDirection, //determines if this node senses the gradient along x or y
+
+    outputs: Vec<Rc<RefCell<Box<dyn BaseNode>>>>, //all node this node gives output to
+}
+
+impl FoodGradientNode {
+    pub fn new(rng: &mut dyn RngCore) -> FoodGradientNode {
+        FoodGradientNode {
+            gradient_direction: if rng.gen() {
+                GradientDirection::X
+            } else {
+                GradientDirection::Y
+            },
+            outputs: Vec::new(),
+        }
+    }
+}
+
+impl BaseNode for FoodGradientNode {
+    fn calculate_output(&mut self, _input: f32, agent: usize, world: &mut World) -> f32 {
+        let (i, j) = world
+            .geometry
+            .cell(world.agents[agent].x, world.agents[agent].y);
+        let (di, dj) = match self.gradient_direction {
+            GradientDirection::X => (1, 0),
+            GradientDirection::Y => (0, 1),
+        };
+
+        //cells past the edge of the world count as the same as the current cell
+        let food_at = |offset: i32| match world.geometry.neighbour(i, j, di * offset, dj * offset) {
+            Some((ni, nj)) => world.food[ni][nj],
+            None => world.food[i][j],
+        };
+
+        //positive when the richer cell is in the positive direction, negative when it is behind
+        (food_at(1) - food_at(-1)).clamp(-1.0, 1.0)
+    }
+
+    fn add_input(&mut self, _node: Rc<RefCell<Box<dyn BaseNode>>>, _weight: f32, _innovation: u64) {
+    }
+
+    fn accepts_inputs(&self) -> bool {
+        false
+    }
+
+    fn add_output(&mut self, node: Rc<RefCell<Box<dyn BaseNode>>>) {
+        self.outputs.push(Rc::clone(&node));
+    }
+
+    fn remove_input(&mut self, _index: usize) {}
+
+    fn remove_output(&mut self, node: &Rc<RefCell<Box<dyn BaseNode>>>) {
+        if let Some(i) = self
+            .outputs
+            .iter()
+            .position(|output| Rc::ptr_eq(output, node))
+        {
+            self.outputs.remove(i);
+        }
+    }
+
+    fn get_inputs(&self) -> &[Rc<RefCell<Box<dyn BaseNode>>>] {
+        &[]
+    }
+
+    fn get_weights(&self) -> &[f32] {
+        &[]
+    }
+
+    fn get_weights_mut(&mut self) -> &mut [f32] {
+        &mut []
+    }
+
+    fn get_innovations(&self) -> &[u64] {
+        &[]
+    }
+
+    fn get_bias(&self) -> f32 {
+        0.0
+    }
+
+    fn set_bias(&mut self, _bias: f32) {}
+
+    fn get_outputs(&self) -> &[Rc<RefCell<Box<dyn BaseNode>>>] {
+        &self.outputs
+    }
+
+    fn disconnect(&mut self) {
+        self.outputs.clear();
+    }
+
+    fn clone_node(&self) -> Box<dyn BaseNode> {
+        Box::new(FoodGradientNode {
+            gradient_direction: self.gradient_direction,
+            outputs: Vec::new(),
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "FoodGradientNode"
+    }
+
+    fn get_parameters(&self) -> NodeParameters {
+        NodeParameters::FoodGradientNode {
+            gradient_direction: self.gradient_direction,
+        }
+    }
+
+    fn set_parameters(&mut self, parameters: &NodeParameters) {
+        if let NodeParameters::FoodGradientNode { gradient_direction } = *parameters {
+            self.gradient_direction = gradient_direction;
+        }
+    }
+}
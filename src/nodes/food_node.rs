@@ -0,0 +1,91 @@
+use super::{parameters::NodeParameters, BaseNode, World};
+use std::{cell::RefCell, rc::Rc};
+pub struct FoodNode {
+    // Define the properties of the node here
+    outputs: Vec<Rc<RefCell<Box<dyn BaseNode>>>>, //all node this node gives output to
+}
+
+impl FoodNode {
+    pub fn new() -> FoodNode {
+        FoodNode {
+            outputs: Vec::new(),
+        }
+    }
+}
+
+impl BaseNode for FoodNode {
+    fn calculate_output(&mut self, _input: f32, agent: usize, world: &mut World) -> f32 {
+        //sense the food in the cell the agent is standing on
+        let (i, j) = world
+            .geometry
+            .cell(world.agents[agent].x, world.agents[agent].y);
+        world.food[i][j].min(1.0)
+    }
+
+    fn add_input(&mut self, _node: Rc<RefCell<Box<dyn BaseNode>>>, _weight: f32, _innovation: u64) {
+    }
+
+    fn accepts_inputs(&self) -> bool {
+        false
+    }
+
+    fn add_output(&mut self, node: Rc<RefCell<Box<dyn BaseNode>>>) {
+        self.outputs.push(Rc::clone(&node));
+    }
+
+    fn remove_input(&mut self, _index: usize) {}
+
+    fn remove_output(&mut self, node: &Rc<RefCell<Box<dyn BaseNode>>>) {
+        if let Some(i) = self
+            .outputs
+            .iter()
+            .position(|output| Rc::ptr_eq(output, node))
+        {
+            self.outputs.remove(i);
+        }
+    }
+
+    fn get_inputs(&self) -> &[Rc<RefCell<Box<dyn BaseNode>>>] {
+        &[]
+    }
+
+    fn get_weights(&self) -> &[f32] {
+        &[]
+    }
+
+    fn get_weights_mut(&mut self) -> &mut [f32] {
+        &mut []
+    }
+
+    fn get_innovations(&self) -> &[u64] {
+        &[]
+    }
+
+    fn get_bias(&self) -> f32 {
+        0.0
+    }
+
+    fn set_bias(&mut self, _bias: f32) {}
+
+    fn get_outputs(&self) -> &[Rc<RefCell<Box<dyn BaseNode>>>] {
+        &self.outputs
+    }
+
+    fn disconnect(&mut self) {
+        self.outputs.clear();
+    }
+
+    fn clone_node(&self) -> Box<dyn BaseNode> {
+        Box::new(FoodNode::new())
+    }
+
+    fn name(&self) -> &'static str {
+        "FoodNode"
+    }
+
+    fn get_parameters(&self) -> NodeParameters {
+        NodeParameters::FoodNode
+    }
+
+    fn set_parameters(&mut self, _parameters: &NodeParameters) {}
+}
@@ -0,0 +1,147 @@
+use std::{cell::RefCell, rc::Rc};
+
+use super::{activation::Activation, parameters::NodeParameters, BaseNode, World};
+use crate::agent::MOVE_COST;
+use crate::mutation::MutationConfig;
+use rand::RngCore;
+
+pub struct ForwardNode {
+    // Define the properties of the node here
+    inputs: Vec<Rc<RefCell<Box<dyn BaseNode>>>>, //all nodes that give input to this node
+    weights: Vec<f32>,                           //weights for each input
+    innovations: Vec<u64>,                       //innovation number of each input connection
+    bias: f32,                                   //bias for the node
+    activation: Activation, //activation function applied to the weighted inputs
+
+    outputs: Vec<Rc<RefCell<Box<dyn BaseNode>>>>, //all node this node gives output to
+}
+
+impl ForwardNode {
+    pub fn new(rng: &mut dyn RngCore) -> ForwardNode {
+        ForwardNode {
+            inputs: Vec::new(),
+            weights: Vec::new(),
+            innovations: Vec::new(),
+            outputs: Vec::new(),
+            bias: 0.0,
+            activation: Activation::random(rng),
+        }
+    }
+}
+
+impl BaseNode for ForwardNode {
+    fn calculate_output(&mut self, input: f32, agent: usize, world: &mut World) -> f32 {
+        //apply activation function on the weighted inputs, effectors can't go past full strength
+        let output = self.activation.apply(input).clamp(-1.0, 1.0);
+
+        //move the agent along its heading, backwards if the output is negative
+        let geometry = world.geometry;
+        let agent = &mut world.agents[agent];
+        let x = agent.x + agent.heading.cos() * output;
+        let y = agent.y + agent.heading.sin() * output;
+
+        //moving costs energy
+        agent.energy -= output.abs() * MOVE_COST;
+        agent.distance_travelled += output.abs();
+
+        //keep the agent inside the world, bouncing off the walls turns it around
+        agent.heading = geometry.reflect_heading(x, y, agent.heading);
+        (agent.x, agent.y) = geometry.bound(x, y);
+
+        output
+    }
+
+    fn add_input(&mut self, node: Rc<RefCell<Box<dyn BaseNode>>>, weight: f32, innovation: u64) {
+        self.inputs.push(Rc::clone(&node));
+        self.weights.push(weight);
+        self.innovations.push(innovation);
+    }
+
+    fn add_output(&mut self, node: Rc<RefCell<Box<dyn BaseNode>>>) {
+        self.outputs.push(Rc::clone(&node));
+    }
+
+    fn remove_input(&mut self, index: usize) {
+        self.inputs.remove(index);
+        self.weights.remove(index);
+        self.innovations.remove(index);
+    }
+
+    fn remove_output(&mut self, node: &Rc<RefCell<Box<dyn BaseNode>>>) {
+        if let Some(i) = self
+            .outputs
+            .iter()
+            .position(|output| Rc::ptr_eq(output, node))
+        {
+            self.outputs.remove(i);
+        }
+    }
+
+    fn get_inputs(&self) -> &[Rc<RefCell<Box<dyn BaseNode>>>] {
+        &self.inputs
+    }
+
+    fn get_weights(&self) -> &[f32] {
+        &self.weights
+    }
+
+    fn get_weights_mut(&mut self) -> &mut [f32] {
+        &mut self.weights
+    }
+
+    fn get_innovations(&self) -> &[u64] {
+        &self.innovations
+    }
+
+    fn get_bias(&self) -> f32 {
+        self.bias
+    }
+
+    fn set_bias(&mut self, bias: f32) {
+        self.bias = bias;
+    }
+
+    fn get_outputs(&self) -> &[Rc<RefCell<Box<dyn BaseNode>>>] {
+        &self.outputs
+    }
+
+    fn disconnect(&mut self) {
+        self.inputs.clear();
+        self.weights.clear();
+        self.innovations.clear();
+        self.outputs.clear();
+    }
+
+    fn clone_node(&self) -> Box<dyn BaseNode> {
+        Box::new(ForwardNode {
+            inputs: Vec::new(),
+            weights: Vec::new(),
+            innovations: Vec::new(),
+            outputs: Vec::new(),
+            bias: self.bias,
+            activation: self.activation,
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "ForwardNode"
+    }
+
+    fn get_parameters(&self) -> NodeParameters {
+        NodeParameters::ForwardNode {
+            bias: self.bias,
+            activation: self.activation,
+        }
+    }
+
+    fn set_parameters(&mut self, parameters: &NodeParameters) {
+        if let NodeParameters::ForwardNode { bias, activation } = *parameters {
+            self.bias = bias;
+            self.activation = activation;
+        }
+    }
+
+    fn mutate(&mut self, config: &MutationConfig, rng: &mut dyn RngCore) {
+        self.activation = self.activation.mutate(config, rng);
+    }
+}
@@ -0,0 +1,143 @@
+use super::{parameters::NodeParameters, BaseNode, World};
+use std::{cell::RefCell, rc::Rc};
+
+const LATCH_THRESHOLD: f32 = 0.5; //input needed to set (above) or reset (below the negative) the latch
+
+pub struct LatchNode {
+    // Define the properties of the node here
+    inputs: Vec<Rc<RefCell<Box<dyn BaseNode>>>>, //all nodes that give input to this node
+    weights: Vec<f32>,                           //weights for each input
+    innovations: Vec<u64>,                       //innovation number of each input connection
+    bias: f32,                                   //bias for the node
+
+    outputs: Vec<Rc<RefCell<Box<dyn BaseNode>>>>, //all node this node gives output to
+    state: bool,                                  //whether the latch is set, kept across frames
+}
+
+impl LatchNode {
+    pub fn new() -> LatchNode {
+        LatchNode {
+            inputs: Vec::new(),
+            weights: Vec::new(),
+            innovations: Vec::new(),
+            outputs: Vec::new(),
+            bias: 0.0,
+            state: false,
+        }
+    }
+}
+
+impl BaseNode for LatchNode {
+    fn calculate_output(&mut self, input: f32, _agent: usize, _world: &mut World) -> f32 {
+        //a strong positive input sets the latch, a strong negative input resets it, anything else holds it
+        if input > LATCH_THRESHOLD {
+            self.state = true;
+        } else if input < -LATCH_THRESHOLD {
+            self.state = false;
+        }
+
+        if self.state {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    fn add_input(&mut self, node: Rc<RefCell<Box<dyn BaseNode>>>, weight: f32, innovation: u64) {
+        self.inputs.push(Rc::clone(&node));
+        self.weights.push(weight);
+        self.innovations.push(innovation);
+    }
+
+    fn add_output(&mut self, node: Rc<RefCell<Box<dyn BaseNode>>>) {
+        self.outputs.push(Rc::clone(&node));
+    }
+
+    fn remove_input(&mut self, index: usize) {
+        self.inputs.remove(index);
+        self.weights.remove(index);
+        self.innovations.remove(index);
+    }
+
+    fn remove_output(&mut self, node: &Rc<RefCell<Box<dyn BaseNode>>>) {
+        if let Some(i) = self
+            .outputs
+            .iter()
+            .position(|output| Rc::ptr_eq(output, node))
+        {
+            self.outputs.remove(i);
+        }
+    }
+
+    fn get_inputs(&self) -> &[Rc<RefCell<Box<dyn BaseNode>>>] {
+        &self.inputs
+    }
+
+    fn get_weights(&self) -> &[f32] {
+        &self.weights
+    }
+
+    fn get_weights_mut(&mut self) -> &mut [f32] {
+        &mut self.weights
+    }
+
+    fn get_innovations(&self) -> &[u64] {
+        &self.innovations
+    }
+
+    fn get_bias(&self) -> f32 {
+        self.bias
+    }
+
+    fn set_bias(&mut self, bias: f32) {
+        self.bias = bias;
+    }
+
+    fn get_outputs(&self) -> &[Rc<RefCell<Box<dyn BaseNode>>>] {
+        &self.outputs
+    }
+
+    fn disconnect(&mut self) {
+        self.inputs.clear();
+        self.weights.clear();
+        self.innovations.clear();
+        self.outputs.clear();
+    }
+
+    fn clone_node(&self) -> Box<dyn BaseNode> {
+        Box::new(LatchNode {
+            inputs: Vec::new(),
+            weights: Vec::new(),
+            innovations: Vec::new(),
+            outputs: Vec::new(),
+            bias: self.bias,
+            state: false,
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "LatchNode"
+    }
+
+    fn get_state(&self) -> f32 {
+        if self.state {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    fn set_state(&mut self, state: f32) {
+        self.state = state > 0.5;
+    }
+
+    fn get_parameters(&self) -> NodeParameters {
+        NodeParameters::LatchNode { bias: self.bias }
+    }
+
+    fn set_parameters(&mut self, parameters: &NodeParameters) {
+        if let NodeParameters::LatchNode { bias } = *parameters {
+            self.bias = bias;
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use super::{activation::Activation, parameters::NodeParameters, BaseNode, World};
+use crate::mutation::MutationConfig;
+use rand::{Rng, RngCore};
+use std::{cell::RefCell, rc::Rc};
+
+pub struct MemoryNode {
+    // Define the properties of the node here
+    inputs: Vec<Rc<RefCell<Box<dyn BaseNode>>>>, //all nodes that give input to this node
+    weights: Vec<f32>,                           //weights for each input
+    innovations: Vec<u64>,                       //innovation number of each input connection
+    bias: f32,                                   //bias for the node
+    activation: Activation, //activation function applied to the weighted inputs
+    retention: f32, //fraction of the stored value kept each frame, the rest is replaced by the input
+
+    outputs: Vec<Rc<RefCell<Box<dyn BaseNode>>>>, //all node this node gives output to
+    state: f32,                                   //stored value, kept across frames
+}
+
+impl MemoryNode {
+    pub fn new(rng: &mut dyn RngCore) -> MemoryNode {
+        MemoryNode {
+            inputs: Vec::new(),
+            weights: Vec::new(),
+            innovations: Vec::new(),
+            outputs: Vec::new(),
+            bias: 0.0,
+            activation: Activation::random(rng),
+            retention: rng.gen::<f32>(),
+            state: 0.0,
+        }
+    }
+}
+
+impl BaseNode for MemoryNode {
+    fn calculate_output(&mut self, input: f32, _agent: usize, _world: &mut World) -> f32 {
+        //blend the input into the stored value, a retention near 1 remembers for a long time
+        self.state =
+            self.state * self.retention + self.activation.apply(input) * (1.0 - self.retention);
+
+        self.state
+    }
+
+    fn add_input(&mut self, node: Rc<RefCell<Box<dyn BaseNode>>>, weight: f32, innovation: u64) {
+        self.inputs.push(Rc::clone(&node));
+        self.weights.push(weight);
+        self.innovations.push(innovation);
+    }
+
+    fn add_output(&mut self, node: Rc<RefCell<Box<dyn BaseNode>>>) {
+        self.outputs.push(Rc::clone(&node));
+    }
+
+    fn remove_input(&mut self, index: usize) {
+        self.inputs.remove(index);
+        self.weights.remove(index);
+        self.innovations.remove(index);
+    }
+
+    fn remove_output(&mut self, node: &Rc<RefCell<Box<dyn BaseNode>>>) {
+        if let Some(i) = self
+            .outputs
+            .iter()
+            .position(|output| Rc::ptr_eq(output, node))
+        {
+            self.outputs.remove(i);
+        }
+    }
+
+    fn get_inputs(&self) -> &[Rc<RefCell<Box<dyn BaseNode>>>] {
+        &self.inputs
+    }
+
+    fn get_weights(&self) -> &[f32] {
+        &self.weights
+    }
+
+    fn get_weights_mut(&mut self) -> &mut [f32] {
+        &mut self.weights
+    }
+
+    fn get_innovations(&self) -> &[u64] {
+        &self.innovations
+    }
+
+    fn get_bias(&self) -> f32 {
+        self.bias
+    }
+
+    fn set_bias(&mut self, bias: f32) {
+        self.bias = bias;
+    }
+
+    fn get_outputs(&self) -> &[Rc<RefCell<Box<dyn BaseNode>>>] {
+        &self.outputs
+    }
+
+    fn disconnect(&mut self) {
+        self.inputs.clear();
+        self.weights.clear();
+        self.innovations.clear();
+        self.outputs.clear();
+    }
+
+    fn clone_node(&self) -> Box<dyn BaseNode> {
+        Box::new(MemoryNode {
+            inputs: Vec::new(),
+            weights: Vec::new(),
+            innovations: Vec::new(),
+            outputs: Vec::new(),
+            bias: self.bias,
+            activation: self.activation,
+            retention: self.retention,
+            state: 0.0,
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "MemoryNode"
+    }
+
+    fn get_state(&self) -> f32 {
+        self.state
+    }
+
+    fn set_state(&mut self, state: f32) {
+        self.state = state;
+    }
+
+    fn get_parameters(&self) -> NodeParameters {
+        NodeParameters::MemoryNode {
+            bias: self.bias,
+            activation: self.activation,
+            retention: self.retention,
+        }
+    }
+
+    fn set_parameters(&mut self, parameters: &NodeParameters) {
+        if let NodeParameters::MemoryNode {
+            bias,
+            activation,
+            retention,
+        } = *parameters
+        {
+            self.bias = bias;
+            self.activation = activation;
+            self.retention = retention;
+        }
+    }
+
+    fn mutate(&mut self, config: &MutationConfig, rng: &mut dyn RngCore) {
+        self.activation = self.activation.mutate(config, rng);
+        self.retention = (self.retention + (rng.gen::<f32>() * 2.0 - 1.0) * config.retention_drift)
+            .clamp(0.0, 1.0);
+    }
+}
@@ -1,8 +1,13 @@
+use serde::{Deserialize, Serialize};
 use std::{cell::RefCell, rc::Rc};
 
-use super::{Agent, BaseNode, World};
+use super::{activation::Activation, parameters::NodeParameters, BaseNode, World};
+use crate::agent::MOVE_COST;
+use crate::mutation::MutationConfig;
+use rand::{Rng, RngCore};
 
-enum MoveDirection {
+#[derive(Copy, Clone, Serialize, Deserialize)]
+pub enum MoveDirection {
     X,
     Y,
 }
@@ -10,95 +15,155 @@ pub struct MoveNode {
     // Define the properties of the node here
     inputs: Vec<Rc<RefCell<Box<dyn BaseNode>>>>, //all nodes that give input to this node
     weights: Vec<f32>,                           //weights for each input
+    innovations: Vec<u64>,                       //innovation number of each input connection
     bias: f32,                                   //bias for the node
+    activation: Activation, //activation function applied to the weighted inputs
     move_direction: MoveDirection, //determines if this move nodes moves the x or y direction
 
     outputs: Vec<Rc<RefCell<Box<dyn BaseNode>>>>, //all node this node gives output to
-    output: Option<f32>, //this is the value all nodes in the outputs array use, if the Option is None this Node has not been calculated yet
 }
 
 impl MoveNode {
-    pub fn new() -> MoveNode {
+    pub fn new(rng: &mut dyn RngCore) -> MoveNode {
         MoveNode {
             inputs: Vec::new(),
             weights: Vec::new(),
+            innovations: Vec::new(),
             outputs: Vec::new(),
             bias: 0.0,
+            activation: Activation::random(rng),
 
-            move_direction: if rand::random() {
+            move_direction: if rng.gen() {
                 MoveDirection::X
             } else {
                 MoveDirection::Y
             },
-            output: None,
         }
     }
 }
 
 impl BaseNode for MoveNode {
-    fn calculate_output(&mut self, agent: Rc<RefCell<Agent>>, world: Rc<RefCell<World>>) {
-        //calculate output of all input nodes first
-        let mut output: f32 = 0.0;
-
-        assert_eq!(self.inputs.len(), self.weights.len());
-        self.output.replace(0.0); //DONT DELETE. This line is needed to prevent error, otherwise we get in an infinite loop of caluclate_output
-        for (i, input) in self.inputs.iter().enumerate() {
-            if input.borrow().get_output().is_none() {
-                unsafe {
-                    let input_mut = &mut *input.as_ptr();
-                    input_mut.calculate_output(Rc::clone(&agent), Rc::clone(&world));
-                }
-            }
-            output += input.borrow().get_output().unwrap() * self.weights[i];
-        }
-
-        //add bias to output
-        output += self.bias;
-
-        //apply activation function on output
-        output = output.tanh();
-
-        //set the output
-        self.output = Some(output);
+    fn calculate_output(&mut self, input: f32, agent: usize, world: &mut World) -> f32 {
+        //apply activation function on the weighted inputs, effectors can't go past full strength
+        let output = self.activation.apply(input).clamp(-1.0, 1.0);
 
         //move the agent
+        let geometry = world.geometry;
+        let agent = &mut world.agents[agent];
         match self.move_direction {
-            MoveDirection::X => unsafe { (*agent.as_ptr()).x += output },
-            MoveDirection::Y => unsafe { (*agent.as_ptr()).y += output },
+            MoveDirection::X => agent.x += output,
+            MoveDirection::Y => agent.y += output,
         }
 
-        //bounds check
-        if agent.borrow().x > world.borrow().width as f32 {
-            unsafe { (*agent.as_ptr()).x = world.borrow().width as f32 };
-        }
+        //moving costs energy
+        agent.energy -= output.abs() * MOVE_COST;
+        agent.distance_travelled += output.abs();
 
-        if agent.borrow().y > world.borrow().height as f32 {
-            unsafe { (*agent.as_ptr()).y = world.borrow().height as f32 };
-        }
+        //keep the agent inside the world
+        (agent.x, agent.y) = geometry.bound(agent.x, agent.y);
 
-        if agent.borrow().x < 0.0 {
-            unsafe { (*agent.as_ptr()).x = 0.0 };
-        }
-
-        if agent.borrow().y < 0.0 {
-            unsafe { (*agent.as_ptr()).y = 0.0 };
-        }
+        output
     }
 
-    fn add_input(&mut self, node: Rc<RefCell<Box<dyn BaseNode>>>) {
+    fn add_input(&mut self, node: Rc<RefCell<Box<dyn BaseNode>>>, weight: f32, innovation: u64) {
         self.inputs.push(Rc::clone(&node));
-        self.weights.push(1.0);
+        self.weights.push(weight);
+        self.innovations.push(innovation);
     }
 
     fn add_output(&mut self, node: Rc<RefCell<Box<dyn BaseNode>>>) {
         self.outputs.push(Rc::clone(&node));
     }
 
-    fn get_output(&self) -> Option<f32> {
-        self.output
+    fn remove_input(&mut self, index: usize) {
+        self.inputs.remove(index);
+        self.weights.remove(index);
+        self.innovations.remove(index);
+    }
+
+    fn remove_output(&mut self, node: &Rc<RefCell<Box<dyn BaseNode>>>) {
+        if let Some(i) = self
+            .outputs
+            .iter()
+            .position(|output| Rc::ptr_eq(output, node))
+        {
+            self.outputs.remove(i);
+        }
+    }
+
+    fn get_inputs(&self) -> &[Rc<RefCell<Box<dyn BaseNode>>>] {
+        &self.inputs
+    }
+
+    fn get_weights(&self) -> &[f32] {
+        &self.weights
+    }
+
+    fn get_weights_mut(&mut self) -> &mut [f32] {
+        &mut self.weights
+    }
+
+    fn get_innovations(&self) -> &[u64] {
+        &self.innovations
+    }
+
+    fn get_bias(&self) -> f32 {
+        self.bias
+    }
+
+    fn set_bias(&mut self, bias: f32) {
+        self.bias = bias;
+    }
+
+    fn get_outputs(&self) -> &[Rc<RefCell<Box<dyn BaseNode>>>] {
+        &self.outputs
+    }
+
+    fn disconnect(&mut self) {
+        self.inputs.clear();
+        self.weights.clear();
+        self.innovations.clear();
+        self.outputs.clear();
+    }
+
+    fn clone_node(&self) -> Box<dyn BaseNode> {
+        Box::new(MoveNode {
+            inputs: Vec::new(),
+            weights: Vec::new(),
+            innovations: Vec::new(),
+            outputs: Vec::new(),
+            bias: self.bias,
+            activation: self.activation,
+            move_direction: self.move_direction,
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "MoveNode"
+    }
+
+    fn get_parameters(&self) -> NodeParameters {
+        NodeParameters::MoveNode {
+            bias: self.bias,
+            activation: self.activation,
+            move_direction: self.move_direction,
+        }
+    }
+
+    fn set_parameters(&mut self, parameters: &NodeParameters) {
+        if let NodeParameters::MoveNode {
+            bias,
+            activation,
+            move_direction,
+        } = *parameters
+        {
+            self.bias = bias;
+            self.activation = activation;
+            self.move_direction = move_direction;
+        }
     }
 
-    fn reset_output(&mut self) {
-        self.output = None;
+    fn mutate(&mut self, config: &MutationConfig, rng: &mut dyn RngCore) {
+        self.activation = self.activation.mutate(config, rng);
     }
 }
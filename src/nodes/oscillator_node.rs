@@ -0,0 +1,112 @@
+use super::{parameters::NodeParameters, BaseNode, World};
+use crate::mutation::MutationConfig;
+use rand::{Rng, RngCore};
+use std::{cell::RefCell, f32::consts::TAU, rc::Rc};
+
+const MIN_FREQUENCY: f32 = 0.001; //slowest oscillation in cycles per frame
+const MAX_FREQUENCY: f32 = 0.1; //fastest oscillation in cycles per frame
+pub struct OscillatorNode {
+    // Define the properties of the node here
+    frequency: f32, //cycles per frame of the sine wave
+
+    outputs: Vec<Rc<RefCell<Box<dyn BaseNode>>>>, //all node this node gives output to
+}
+
+impl OscillatorNode {
+    pub fn new(rng: &mut dyn RngCore) -> OscillatorNode {
+        OscillatorNode {
+            //spread the frequencies evenly on a log scale
+            frequency: MIN_FREQUENCY * (MAX_FREQUENCY / MIN_FREQUENCY).powf(rng.gen::<f32>()),
+            outputs: Vec::new(),
+        }
+    }
+}
+
+impl BaseNode for OscillatorNode {
+    fn calculate_output(&mut self, _input: f32, agent: usize, world: &mut World) -> f32 {
+        //the agent's age is the clock, so every agent starts its wave at 0
+        (TAU * self.frequency * world.agents[agent].age as f32).sin()
+    }
+
+    fn add_input(&mut self, _node: Rc<RefCell<Box<dyn BaseNode>>>, _weight: f32, _innovation: u64) {
+    }
+
+    fn accepts_inputs(&self) -> bool {
+        false
+    }
+
+    fn add_output(&mut self, node: Rc<RefCell<Box<dyn BaseNode>>>) {
+        self.outputs.push(Rc::clone(&node));
+    }
+
+    fn remove_input(&mut self, _index: usize) {}
+
+    fn remove_output(&mut self, node: &Rc<RefCell<Box<dyn BaseNode>>>) {
+        if let Some(i) = self
+            .outputs
+            .iter()
+            .position(|output| Rc::ptr_eq(output, node))
+        {
+            self.outputs.remove(i);
+        }
+    }
+
+    fn get_inputs(&self) -> &[Rc<RefCell<Box<dyn BaseNode>>>] {
+        &[]
+    }
+
+    fn get_weights(&self) -> &[f32] {
+        &[]
+    }
+
+    fn get_weights_mut(&mut self) -> &mut [f32] {
+        &mut []
+    }
+
+    fn get_innovations(&self) -> &[u64] {
+        &[]
+    }
+
+    fn get_bias(&self) -> f32 {
+        0.0
+    }
+
+    fn set_bias(&mut self, _bias: f32) {}
+
+    fn get_outputs(&self) -> &[Rc<RefCell<Box<dyn BaseNode>>>] {
+        &self.outputs
+    }
+
+    fn disconnect(&mut self) {
+        self.outputs.clear();
+    }
+
+    fn clone_node(&self) -> Box<dyn BaseNode> {
+        Box::new(OscillatorNode {
+            frequency: self.frequency,
+            outputs: Vec::new(),
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "OscillatorNode"
+    }
+
+    fn get_parameters(&self) -> NodeParameters {
+        NodeParameters::OscillatorNode {
+            frequency: self.frequency,
+        }
+    }
+
+    fn set_parameters(&mut self, parameters: &NodeParameters) {
+        if let NodeParameters::OscillatorNode { frequency } = *parameters {
+            self.frequency = frequency;
+        }
+    }
+
+    fn mutate(&mut self, config: &MutationConfig, rng: &mut dyn RngCore) {
+        //scale the frequency up or down by up to frequency_drift
+        let scale = 1.0 + (rng.gen::<f32>() * 2.0 - 1.0) * config.frequency_drift;
+        self.frequency = (self.frequency * scale).clamp(MIN_FREQUENCY, MAX_FREQUENCY);
+    }
+}
@@ -0,0 +1,86 @@
+use super::activation::Activation;
+use super::food_gradient_node::{FoodGradientNode, GradientDirection};
+use super::move_node::{MoveDirection, MoveNode};
+use super::proprioception_node::{ProprioceptionNode, Sense};
+use super::vision_node::{VisionNode, VisionSense};
+use super::BaseNode;
+use super::{eat_node::EatNode, food_node::FoodNode, forward_node::ForwardNode};
+use super::{latch_node::LatchNode, memory_node::MemoryNode, node::Node};
+use super::{oscillator_node::OscillatorNode, random_node::RandomNode, turn_node::TurnNode};
+use rand::rngs::mock::StepRng;
+use serde::{Deserialize, Serialize};
+
+///a node's type and every parameter that clone_node copies, saved genomes store nodes as this
+#[derive(Copy, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum NodeParameters {
+    RandomNode,
+    Node {
+        bias: f32,
+        activation: Activation,
+    },
+    MoveNode {
+        bias: f32,
+        activation: Activation,
+        move_direction: MoveDirection,
+    },
+    TurnNode {
+        bias: f32,
+        activation: Activation,
+    },
+    ForwardNode {
+        bias: f32,
+        activation: Activation,
+    },
+    EatNode {
+        bias: f32,
+        activation: Activation,
+    },
+    FoodNode,
+    FoodGradientNode {
+        gradient_direction: GradientDirection,
+    },
+    VisionNode {
+        angle: f32,
+        sense: VisionSense,
+    },
+    ProprioceptionNode {
+        sense: Sense,
+    },
+    OscillatorNode {
+        frequency: f32,
+    },
+    LatchNode {
+        bias: f32,
+    },
+    MemoryNode {
+        bias: f32,
+        activation: Activation,
+        retention: f32,
+    },
+}
+
+impl NodeParameters {
+    ///builds a node of this type with these parameters and no edges
+    pub fn build(&self) -> Box<dyn BaseNode> {
+        //set_parameters overwrites everything new picks at random, so any rng will do
+        let rng = &mut StepRng::new(0, 0);
+        let mut node: Box<dyn BaseNode> = match self {
+            NodeParameters::RandomNode => Box::new(RandomNode::new()),
+            NodeParameters::Node { .. } => Box::new(Node::new(rng)),
+            NodeParameters::MoveNode { .. } => Box::new(MoveNode::new(rng)),
+            NodeParameters::TurnNode { .. } => Box::new(TurnNode::new(rng)),
+            NodeParameters::ForwardNode { .. } => Box::new(ForwardNode::new(rng)),
+            NodeParameters::EatNode { .. } => Box::new(EatNode::new(rng)),
+            NodeParameters::FoodNode => Box::new(FoodNode::new()),
+            NodeParameters::FoodGradientNode { .. } => Box::new(FoodGradientNode::new(rng)),
+            NodeParameters::VisionNode { .. } => Box::new(VisionNode::new(rng)),
+            NodeParameters::ProprioceptionNode { .. } => Box::new(ProprioceptionNode::new(rng)),
+            NodeParameters::OscillatorNode { .. } => Box::new(OscillatorNode::new(rng)),
+            NodeParameters::LatchNode { .. } => Box::new(LatchNode::new()),
+            NodeParameters::MemoryNode { .. } => Box::new(MemoryNode::new(rng)),
+        };
+        node.set_parameters(self);
+        node
+    }
+}
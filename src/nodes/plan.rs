@@ -0,0 +1,194 @@
+use super::{BaseNode, World};
+use std::{cell::RefCell, collections::HashMap, mem, rc::Rc};
+
+#[derive(Copy, Clone, PartialEq)]
+enum Visit {
+    NotVisited,
+    InProgress,
+    Done,
+}
+
+///A brain flattened into arrays so it can be evaluated without following Rcs.
+///
+///The order comes from a depth first search over each node's inputs, starting from the nodes in brain order.
+///An input that is still in progress when it is reached again closes a cycle, that edge is recurrent and reads
+///the input's value from the previous frame. Every other edge reads a value already calculated this frame.
+#[derive(Default)]
+pub struct BrainPlan {
+    nodes: Vec<Box<dyn BaseNode>>, //copies of the brain's nodes, they keep any state between frames
+    order: Vec<usize>,             //indices of the nodes in the order they are evaluated
+
+    //the inputs of node i are edges input_start[i]..input_start[i + 1]
+    input_start: Vec<usize>,
+    input_source: Vec<usize>,   //index of the node the edge reads from
+    input_weight: Vec<f32>,     //weight of the edge
+    input_recurrent: Vec<bool>, //whether the edge reads last frame's value
+    bias: Vec<f32>,
+
+    values: Vec<f32>,          //output of each node this frame
+    previous_values: Vec<f32>, //output of each node last frame
+}
+
+impl BrainPlan {
+    pub fn compile(brain: &[Rc<RefCell<Box<dyn BaseNode>>>]) -> BrainPlan {
+        let index: HashMap<*const RefCell<Box<dyn BaseNode>>, usize> = brain
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (Rc::as_ptr(node), i))
+            .collect();
+
+        let mut plan = BrainPlan {
+            nodes: Vec::with_capacity(brain.len()),
+            input_start: vec![0],
+            values: vec![0.0; brain.len()],
+            previous_values: vec![0.0; brain.len()],
+            ..Default::default()
+        };
+
+        //flatten the edges, grouped by the node they go into
+        for node in brain {
+            let node = node.borrow();
+            for (input, weight) in node.get_inputs().iter().zip(node.get_weights()) {
+                plan.input_source.push(index[&Rc::as_ptr(input)]);
+                plan.input_weight.push(*weight);
+                plan.input_recurrent.push(false);
+            }
+            plan.input_start.push(plan.input_source.len());
+            plan.bias.push(node.get_bias());
+            plan.nodes.push(node.clone_node());
+        }
+
+        //order the nodes so every input is calculated before the node that reads it, cycles become recurrent edges
+        let mut visits = vec![Visit::NotVisited; brain.len()];
+        for root in 0..brain.len() {
+            if visits[root] != Visit::NotVisited {
+                continue;
+            }
+            visits[root] = Visit::InProgress;
+            let mut stack = vec![(root, plan.input_start[root])];
+            while let Some((node, edge)) = stack.pop() {
+                if edge == plan.input_start[node + 1] {
+                    visits[node] = Visit::Done;
+                    plan.order.push(node);
+                    continue;
+                }
+                stack.push((node, edge + 1));
+
+                let source = plan.input_source[edge];
+                match visits[source] {
+                    Visit::InProgress => plan.input_recurrent[edge] = true,
+                    Visit::NotVisited => {
+                        visits[source] = Visit::InProgress;
+                        stack.push((source, plan.input_start[source]));
+                    }
+                    Visit::Done => {}
+                }
+            }
+        }
+
+        plan
+    }
+
+    ///output of each node last frame in brain order, recurrent edges read these next frame
+    pub fn get_values(&self) -> &[f32] {
+        &self.values
+    }
+
+    pub fn set_values(&mut self, values: &[f32]) {
+        self.values.copy_from_slice(values);
+    }
+
+    ///state each node keeps between frames in brain order, the plan's nodes hold it rather than the brain's
+    pub fn get_states(&self) -> Vec<f32> {
+        self.nodes.iter().map(|node| node.get_state()).collect()
+    }
+
+    pub fn set_states(&mut self, states: &[f32]) {
+        for (node, state) in self.nodes.iter_mut().zip(states) {
+            node.set_state(*state);
+        }
+    }
+
+    ///runs every node once, agent is the index of the agent that owns this brain in world.agents
+    pub fn evaluate(&mut self, agent: usize, world: &mut World) {
+        mem::swap(&mut self.values, &mut self.previous_values);
+
+        for i in 0..self.order.len() {
+            let node = self.order[i];
+
+            let mut input = self.bias[node];
+            for edge in self.input_start[node]..self.input_start[node + 1] {
+                let source = self.input_source[edge];
+                input += self.input_weight[edge]
+                    * if self.input_recurrent[edge] {
+                        self.previous_values[source]
+                    } else {
+                        self.values[source]
+                    };
+            }
+
+            self.values[node] = self.nodes[node].calculate_output(input, agent, world);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::nodes::activation::Activation;
+    use crate::nodes::parameters::NodeParameters;
+    use crate::world::WorldControls;
+    use std::sync::{Arc, Mutex};
+
+    fn identity(bias: f32) -> Rc<RefCell<Box<dyn BaseNode>>> {
+        let parameters = NodeParameters::Node {
+            bias,
+            activation: Activation::Identity,
+        };
+        Rc::new(RefCell::new(parameters.build()))
+    }
+
+    fn connect(
+        input: &Rc<RefCell<Box<dyn BaseNode>>>,
+        output: &Rc<RefCell<Box<dyn BaseNode>>>,
+        weight: f32,
+    ) {
+        input.borrow_mut().add_output(Rc::clone(output));
+        output.borrow_mut().add_input(Rc::clone(input), weight, 0);
+    }
+
+    fn world() -> World {
+        World::new(Arc::new(Mutex::new(WorldControls::new())), &Config::new())
+    }
+
+    #[test]
+    fn inputs_are_evaluated_first_whatever_the_brain_order() {
+        //a -> b -> c, listed backwards so the search has to reorder them
+        let (a, b, c) = (identity(1.0), identity(0.0), identity(0.0));
+        connect(&a, &b, 2.0);
+        connect(&b, &c, 3.0);
+        let mut plan = BrainPlan::compile(&[Rc::clone(&c), Rc::clone(&b), Rc::clone(&a)]);
+
+        plan.evaluate(0, &mut world());
+        assert_eq!(plan.get_values(), &[6.0, 2.0, 1.0]);
+    }
+
+    #[test]
+    fn recurrent_edge_reads_the_previous_frame() {
+        //a -> b and b -> a, searching from a makes a -> b the recurrent edge
+        let (a, b) = (identity(1.0), identity(0.0));
+        connect(&a, &b, 2.0);
+        connect(&b, &a, 3.0);
+        let mut plan = BrainPlan::compile(&[Rc::clone(&a), Rc::clone(&b)]);
+        let mut world = world();
+
+        //b reads a's value from before the first frame, which is 0
+        plan.evaluate(0, &mut world);
+        assert_eq!(plan.get_values(), &[1.0, 0.0]);
+
+        //b = 2 * 1 from the last frame, then a = 1 + 3 * 2
+        plan.evaluate(0, &mut world);
+        assert_eq!(plan.get_values(), &[7.0, 2.0]);
+    }
+}
@@ -0,0 +1,126 @@
+use super::{parameters::NodeParameters, BaseNode, World};
+use crate::agent::REPRODUCTION_ENERGY;
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::{cell::RefCell, f32::consts::PI, rc::Rc};
+
+const AGE_SCALE: f32 = 5000.0; //age in frames that is sensed as fully old
+
+#[derive(Copy, Clone, Serialize, Deserialize)]
+pub enum Sense {
+    Energy,  //stored energy, 1 when the agent has enough to reproduce
+    Age,     //frames lived, 1 once the agent is AGE_SCALE frames old
+    X,       //x position as a fraction of the world width
+    Y,       //y position as a fraction of the world height
+    Heading, //heading mapped to [-1,1)
+}
+pub struct ProprioceptionNode {
+    // Define the properties of the node here
+    sense: Sense, //determines which part of the agent's state this node outputs
+
+    outputs: Vec<Rc<RefCell<Box<dyn BaseNode>>>>, //all node this node gives output to
+}
+
+impl ProprioceptionNode {
+    pub fn new(rng: &mut dyn RngCore) -> ProprioceptionNode {
+        ProprioceptionNode {
+            sense: match rng.gen_range(0..5) {
+                0 => Sense::Energy,
+                1 => Sense::Age,
+                2 => Sense::X,
+                3 => Sense::Y,
+                4 => Sense::Heading,
+                _ => panic!("Random number generator failed"),
+            },
+            outputs: Vec::new(),
+        }
+    }
+}
+
+impl BaseNode for ProprioceptionNode {
+    fn calculate_output(&mut self, _input: f32, agent: usize, world: &mut World) -> f32 {
+        let geometry = world.geometry;
+        let agent = &world.agents[agent];
+        match self.sense {
+            Sense::Energy => (agent.energy / REPRODUCTION_ENERGY).clamp(0.0, 1.0),
+            Sense::Age => (agent.age as f32 / AGE_SCALE).min(1.0),
+            Sense::X => agent.x / geometry.width as f32,
+            Sense::Y => agent.y / geometry.height as f32,
+            Sense::Heading => agent.heading / PI - 1.0,
+        }
+    }
+
+    fn add_input(&mut self, _node: Rc<RefCell<Box<dyn BaseNode>>>, _weight: f32, _innovation: u64) {
+    }
+
+    fn accepts_inputs(&self) -> bool {
+        false
+    }
+
+    fn add_output(&mut self, node: Rc<RefCell<Box<dyn BaseNode>>>) {
+        self.outputs.push(Rc::clone(&node));
+    }
+
+    fn remove_input(&mut self, _index: usize) {}
+
+    fn remove_output(&mut self, node: &Rc<RefCell<Box<dyn BaseNode>>>) {
+        if let Some(i) = self
+            .outputs
+            .iter()
+            .position(|output| Rc::ptr_eq(output, node))
+        {
+            self.outputs.remove(i);
+        }
+    }
+
+    fn get_inputs(&self) -> &[Rc<RefCell<Box<dyn BaseNode>>>] {
+        &[]
+    }
+
+    fn get_weights(&self) -> &[f32] {
+        &[]
+    }
+
+    fn get_weights_mut(&mut self) -> &mut [f32] {
+        &mut []
+    }
+
+    fn get_innovations(&self) -> &[u64] {
+        &[]
+    }
+
+    fn get_bias(&self) -> f32 {
+        0.0
+    }
+
+    fn set_bias(&mut self, _bias: f32) {}
+
+    fn get_outputs(&self) -> &[Rc<RefCell<Box<dyn BaseNode>>>] {
+        &self.outputs
+    }
+
+    fn disconnect(&mut self) {
+        self.outputs.clear();
+    }
+
+    fn clone_node(&self) -> Box<dyn BaseNode> {
+        Box::new(ProprioceptionNode {
+            sense: self.sense,
+            outputs: Vec::new(),
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "ProprioceptionNode"
+    }
+
+    fn get_parameters(&self) -> NodeParameters {
+        NodeParameters::ProprioceptionNode { sense: self.sense }
+    }
+
+    fn set_parameters(&mut self, parameters: &NodeParameters) {
+        if let NodeParameters::ProprioceptionNode { sense } = *parameters {
+            self.sense = sense;
+        }
+    }
+}
@@ -1,36 +1,88 @@
-use super::{Agent, BaseNode, World};
+use super::{parameters::NodeParameters, BaseNode, World};
+use rand::Rng;
 use std::{cell::RefCell, rc::Rc};
 pub struct RandomNode {
     // Define the properties of the node here
     outputs: Vec<Rc<RefCell<Box<dyn BaseNode>>>>, //all node this node gives output to
-    output: Option<f32>, //this is the value all nodes in the outputs array use, if the Option is None this Node has not been calculated yet
 }
 
 impl RandomNode {
     pub fn new() -> RandomNode {
         RandomNode {
             outputs: Vec::new(),
-            output: None,
         }
     }
 }
 
 impl BaseNode for RandomNode {
-    fn calculate_output(&mut self, _agent: Rc<RefCell<Agent>>, _world: Rc<RefCell<World>>) {
-        self.output = Some(rand::random::<f32>() * 2.0 - 1.0); //random number [-1,1]
+    fn calculate_output(&mut self, _input: f32, _agent: usize, world: &mut World) -> f32 {
+        world.rng.gen::<f32>() * 2.0 - 1.0 //random number [-1,1]
     }
 
-    fn add_input(&mut self, _node: Rc<RefCell<Box<dyn BaseNode>>>) {}
+    fn add_input(&mut self, _node: Rc<RefCell<Box<dyn BaseNode>>>, _weight: f32, _innovation: u64) {
+    }
+
+    fn accepts_inputs(&self) -> bool {
+        false
+    }
 
     fn add_output(&mut self, node: Rc<RefCell<Box<dyn BaseNode>>>) {
         self.outputs.push(Rc::clone(&node));
     }
 
-    fn get_output(&self) -> Option<f32> {
-        self.output
+    fn remove_input(&mut self, _index: usize) {}
+
+    fn remove_output(&mut self, node: &Rc<RefCell<Box<dyn BaseNode>>>) {
+        if let Some(i) = self
+            .outputs
+            .iter()
+            .position(|output| Rc::ptr_eq(output, node))
+        {
+            self.outputs.remove(i);
+        }
+    }
+
+    fn get_inputs(&self) -> &[Rc<RefCell<Box<dyn BaseNode>>>] {
+        &[]
+    }
+
+    fn get_weights(&self) -> &[f32] {
+        &[]
+    }
+
+    fn get_weights_mut(&mut self) -> &mut [f32] {
+        &mut []
+    }
+
+    fn get_innovations(&self) -> &[u64] {
+        &[]
     }
 
-    fn reset_output(&mut self) {
-        self.output = None;
+    fn get_bias(&self) -> f32 {
+        0.0
     }
+
+    fn set_bias(&mut self, _bias: f32) {}
+
+    fn get_outputs(&self) -> &[Rc<RefCell<Box<dyn BaseNode>>>] {
+        &self.outputs
+    }
+
+    fn disconnect(&mut self) {
+        self.outputs.clear();
+    }
+
+    fn clone_node(&self) -> Box<dyn BaseNode> {
+        Box::new(RandomNode::new())
+    }
+
+    fn name(&self) -> &'static str {
+        "RandomNode"
+    }
+
+    fn get_parameters(&self) -> NodeParameters {
+        NodeParameters::RandomNode
+    }
+
+    fn set_parameters(&mut self, _parameters: &NodeParameters) {}
 }
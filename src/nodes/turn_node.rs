@@ -0,0 +1,137 @@
+use std::{cell::RefCell, f32::consts::TAU, rc::Rc};
+
+use super::{activation::Activation, parameters::NodeParameters, BaseNode, World};
+use crate::agent::MAX_TURN;
+use crate::mutation::MutationConfig;
+use rand::RngCore;
+
+pub struct TurnNode {
+    // Define the properties of the node here
+    inputs: Vec<Rc<RefCell<Box<dyn BaseNode>>>>, //all nodes that give input to this node
+    weights: Vec<f32>,                           //weights for each input
+    innovations: Vec<u64>,                       //innovation number of each input connection
+    bias: f32,                                   //bias for the node
+    activation: Activation, //activation function applied to the weighted inputs
+
+    outputs: Vec<Rc<RefCell<Box<dyn BaseNode>>>>, //all node this node gives output to
+}
+
+impl TurnNode {
+    pub fn new(rng: &mut dyn RngCore) -> TurnNode {
+        TurnNode {
+            inputs: Vec::new(),
+            weights: Vec::new(),
+            innovations: Vec::new(),
+            outputs: Vec::new(),
+            bias: 0.0,
+            activation: Activation::random(rng),
+        }
+    }
+}
+
+impl BaseNode for TurnNode {
+    fn calculate_output(&mut self, input: f32, agent: usize, world: &mut World) -> f32 {
+        //apply activation function on the weighted inputs, effectors can't go past full strength
+        let output = self.activation.apply(input).clamp(-1.0, 1.0);
+
+        //turn the agent
+        let agent = &mut world.agents[agent];
+        agent.heading = (agent.heading + output * MAX_TURN).rem_euclid(TAU);
+
+        output
+    }
+
+    fn add_input(&mut self, node: Rc<RefCell<Box<dyn BaseNode>>>, weight: f32, innovation: u64) {
+        self.inputs.push(Rc::clone(&node));
+        self.weights.push(weight);
+        self.innovations.push(innovation);
+    }
+
+    fn add_output(&mut self, node: Rc<RefCell<Box<dyn BaseNode>>>) {
+        self.outputs.push(Rc::clone(&node));
+    }
+
+    fn remove_input(&mut self, index: usize) {
+        self.inputs.remove(index);
+        self.weights.remove(index);
+        self.innovations.remove(index);
+    }
+
+    fn remove_output(&mut self, node: &Rc<RefCell<Box<dyn BaseNode>>>) {
+        if let Some(i) = self
+            .outputs
+            .iter()
+            .position(|output| Rc::ptr_eq(output, node))
+        {
+            self.outputs.remove(i);
+        }
+    }
+
+    fn get_inputs(&self) -> &[Rc<RefCell<Box<dyn BaseNode>>>] {
+        &self.inputs
+    }
+
+    fn get_weights(&self) -> &[f32] {
+        &self.weights
+    }
+
+    fn get_weights_mut(&mut self) -> &mut [f32] {
+        &mut self.weights
+    }
+
+    fn get_innovations(&self) -> &[u64] {
+        &self.innovations
+    }
+
+    fn get_bias(&self) -> f32 {
+        self.bias
+    }
+
+    fn set_bias(&mut self, bias: f32) {
+        self.bias = bias;
+    }
+
+    fn get_outputs(&self) -> &[Rc<RefCell<Box<dyn BaseNode>>>] {
+        &self.outputs
+    }
+
+    fn disconnect(&mut self) {
+        self.inputs.clear();
+        self.weights.clear();
+        self.innovations.clear();
+        self.outputs.clear();
+    }
+
+    fn clone_node(&self) -> Box<dyn BaseNode> {
+        Box::new(TurnNode {
+            inputs: Vec::new(),
+            weights: Vec::new(),
+            innovations: Vec::new(),
+            outputs: Vec::new(),
+            bias: self.bias,
+            activation: self.activation,
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "TurnNode"
+    }
+
+    fn get_parameters(&self) -> NodeParameters {
+        NodeParameters::TurnNode {
+            bias: self.bias,
+            activation: self.activation,
+        }
+    }
+
+    fn set_parameters(&mut self, parameters: &NodeParameters) {
+        if let NodeParameters::TurnNode { bias, activation } = *parameters {
+            self.bias = bias;
+            self.activation = activation;
+        }
+    }
+
+    fn mutate(&mut self, config: &MutationConfig, rng: &mut dyn RngCore) {
+        self.activation = self.activation.mutate(config, rng);
+    }
+}
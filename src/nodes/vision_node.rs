@@ -0,0 +1,204 @@
+use super::{parameters::NodeParameters, BaseNode, World};
+use crate::mutation::MutationConfig;
+use crate::util::color::Color;
+use crate::world::geometry::Boundary;
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::{cell::RefCell, f32::consts::TAU, rc::Rc};
+
+const VISION_RANGE: f32 = 10.0; //furthest distance a ray can see
+const RAY_STEP: f32 = 0.25; //distance between the points checked along a ray
+const AGENT_RADIUS: f32 = 0.5; //how close a ray has to pass to an agent to hit it
+const HIGH_FOOD: f32 = 0.5; //food cells with at least this much food stop a ray
+
+#[derive(Copy, Clone, Serialize, Deserialize)]
+pub enum VisionSense {
+    Distance, //distance to what the ray hit, 1 if it hit nothing
+    Red,      //color channels of what the ray hit, 0 if it hit nothing
+    Green,
+    Blue,
+}
+pub struct VisionNode {
+    // Define the properties of the node here
+    angle: f32,         //direction of the ray in radians relative to the agent's heading
+    sense: VisionSense, //determines which property of what the ray hit this node outputs
+
+    outputs: Vec<Rc<RefCell<Box<dyn BaseNode>>>>, //all node this node gives output to
+}
+
+impl VisionNode {
+    pub fn new(rng: &mut dyn RngCore) -> VisionNode {
+        VisionNode {
+            angle: rng.gen::<f32>() * TAU,
+            sense: match rng.gen_range(0..4) {
+                0 => VisionSense::Distance,
+                1 => VisionSense::Red,
+                2 => VisionSense::Green,
+                3 => VisionSense::Blue,
+                _ => panic!("Random number generator failed"),
+            },
+            outputs: Vec::new(),
+        }
+    }
+
+    ///walks along the ray until it hits a wall, another agent or a high food cell
+    ///returns how far along the ray the hit was and the color of what was hit
+    fn cast_ray(&self, agent: usize, world: &World) -> Option<(f32, Color)> {
+        //the angle is relative to the way the agent is facing
+        let (start_x, start_y) = (world.agents[agent].x, world.agents[agent].y);
+        let angle = world.agents[agent].heading + self.angle;
+        let (dx, dy) = (angle.cos(), angle.sin());
+
+        let mut distance = RAY_STEP;
+        while distance <= VISION_RANGE {
+            let x = start_x + dx * distance;
+            let y = start_y + dy * distance;
+
+            //walls only exist in worlds that don't wrap around
+            if world.geometry.boundary != Boundary::Wrap
+                && (x < 0.0
+                    || y < 0.0
+                    || x > world.geometry.width as f32
+                    || y > world.geometry.height as f32)
+            {
+                return Some((
+                    distance,
+                    Color {
+                        r: 0.5,
+                        g: 0.5,
+                        b: 0.5,
+                    },
+                ));
+            }
+            let (x, y) = world.geometry.bound(x, y);
+            let (i, j) = world.geometry.cell(x, y);
+
+            //agents are seen where they were at the start of the frame, the first one in world.agents wins
+            let hit = world
+                .agent_grid
+                .near(i, j, &world.geometry)
+                .filter(|other| {
+                    other.index != agent
+                        && (other.x - x).powi(2) + (other.y - y).powi(2) <= AGENT_RADIUS.powi(2)
+                })
+                .map(|other| other.index)
+                .min();
+            if let Some(index) = hit {
+                return Some((distance, world.agents[index].color));
+            }
+
+            if world.food[i][j] >= HIGH_FOOD {
+                return Some((
+                    distance,
+                    Color {
+                        r: 0.0,
+                        g: world.food[i][j].min(1.0),
+                        b: 0.0,
+                    },
+                ));
+            }
+
+            distance += RAY_STEP;
+        }
+
+        None
+    }
+}
+
+impl BaseNode for VisionNode {
+    fn calculate_output(&mut self, _input: f32, agent: usize, world: &mut World) -> f32 {
+        match (self.cast_ray(agent, world), self.sense) {
+            (Some((distance, _)), VisionSense::Distance) => distance / VISION_RANGE,
+            (Some((_, color)), VisionSense::Red) => color.r,
+            (Some((_, color)), VisionSense::Green) => color.g,
+            (Some((_, color)), VisionSense::Blue) => color.b,
+            (None, VisionSense::Distance) => 1.0,
+            (None, _) => 0.0,
+        }
+    }
+
+    fn add_input(&mut self, _node: Rc<RefCell<Box<dyn BaseNode>>>, _weight: f32, _innovation: u64) {
+    }
+
+    fn accepts_inputs(&self) -> bool {
+        false
+    }
+
+    fn add_output(&mut self, node: Rc<RefCell<Box<dyn BaseNode>>>) {
+        self.outputs.push(Rc::clone(&node));
+    }
+
+    fn remove_input(&mut self, _index: usize) {}
+
+    fn remove_output(&mut self, node: &Rc<RefCell<Box<dyn BaseNode>>>) {
+        if let Some(i) = self
+            .outputs
+            .iter()
+            .position(|output| Rc::ptr_eq(output, node))
+        {
+            self.outputs.remove(i);
+        }
+    }
+
+    fn get_inputs(&self) -> &[Rc<RefCell<Box<dyn BaseNode>>>] {
+        &[]
+    }
+
+    fn get_weights(&self) -> &[f32] {
+        &[]
+    }
+
+    fn get_weights_mut(&mut self) -> &mut [f32] {
+        &mut []
+    }
+
+    fn get_innovations(&self) -> &[u64] {
+        &[]
+    }
+
+    fn get_bias(&self) -> f32 {
+        0.0
+    }
+
+    fn set_bias(&mut self, _bias: f32) {}
+
+    fn get_outputs(&self) -> &[Rc<RefCell<Box<dyn BaseNode>>>] {
+        &self.outputs
+    }
+
+    fn disconnect(&mut self) {
+        self.outputs.clear();
+    }
+
+    fn clone_node(&self) -> Box<dyn BaseNode> {
+        Box::new(VisionNode {
+            angle: self.angle,
+            sense: self.sense,
+            outputs: Vec::new(),
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "VisionNode"
+    }
+
+    fn get_parameters(&self) -> NodeParameters {
+        NodeParameters::VisionNode {
+            angle: self.angle,
+            sense: self.sense,
+        }
+    }
+
+    fn set_parameters(&mut self, parameters: &NodeParameters) {
+        if let NodeParameters::VisionNode { angle, sense } = *parameters {
+            self.angle = angle;
+            self.sense = sense;
+        }
+    }
+
+    fn mutate(&mut self, config: &MutationConfig, rng: &mut dyn RngCore) {
+        //turn the ray a little
+        self.angle =
+            (self.angle + (rng.gen::<f32>() * 2.0 - 1.0) * config.angle_drift).rem_euclid(TAU);
+    }
+}
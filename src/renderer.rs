@@ -31,6 +31,8 @@ pub enum ClickAction {
     PlayPause,
     Step,
     SpeedChange,
+    Save,
+    Load,
     None,
 }
 pub struct GraphicsRenderer<T: Renderer> {
@@ -99,23 +101,25 @@ impl<T: Renderer> GraphicsRenderer<T> {
                     );
                 }
             }
-            ClickAction::StartGame => (*self.world_controls.lock().unwrap()).started = true,
+            ClickAction::StartGame => self.world_controls.lock().unwrap().started = true,
             ClickAction::PlayPause => {
-                let paused_state = (*self.world_controls.lock().unwrap()).paused;
-                (*self.world_controls.lock().unwrap()).paused = !paused_state;
+                let paused_state = self.world_controls.lock().unwrap().paused;
+                self.world_controls.lock().unwrap().paused = !paused_state;
             }
             ClickAction::Step => {
-                (*self.world_controls.lock().unwrap()).step = true;
+                self.world_controls.lock().unwrap().step = true;
             }
             ClickAction::SpeedChange => {
-                let current_speed = (*self.world_controls.lock().unwrap()).speed;
+                let current_speed = self.world_controls.lock().unwrap().speed;
 
-                (*self.world_controls.lock().unwrap()).speed = match current_speed {
+                self.world_controls.lock().unwrap().speed = match current_speed {
                     GameSpeed::Slow => GameSpeed::Medium,
                     GameSpeed::Medium => GameSpeed::Fast,
                     GameSpeed::Fast => GameSpeed::Slow,
                 }
             }
+            ClickAction::Save => self.world_controls.lock().unwrap().save = true,
+            ClickAction::Load => self.world_controls.lock().unwrap().load = true,
             _ => {}
         }
     }
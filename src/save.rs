@@ -0,0 +1,292 @@
+use crate::agent::{Agent, Locomotion};
+use crate::crossover::ConnectionGene;
+use crate::nodes::parameters::NodeParameters;
+use crate::util::color::Color;
+use crate::world::innovation::InnovationRegistry;
+use rand::rngs::mock::StepRng;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::cell::RefCell;
+use std::{collections::HashMap, fmt, fs, io, path::Path, rc::Rc};
+
+//version 1: the first format
+pub const GENOME_VERSION: u32 = 1; //bumped whenever the format changes in a way old files can't be read
+
+///an agent's genome as it is written to disk, as JSON it looks like
+///{
+///  "version": 1,
+///  "color": { "r": 0.2, "g": 0.7, "b": 0.5 },
+///  "locomotion": "Heading",
+///  "nodes": [
+///    { "id": 0, "type": "FoodNode" },
+///    { "id": 1, "type": "MoveNode", "bias": 0.1, "activation": "Tanh", "move_direction": "X" }
+///  ],
+///  "connections": [
+///    { "innovation": 0, "input": 0, "output": 1, "weight": 1.5 }
+///  ]
+///}
+///nodes have a "type" field naming their node type and one field for each of that type's parameters, see NodeParameters,
+///ids are the agent's node ids and stay the same when the genome is loaded again,
+///connections are listed in the order each node reads its inputs, which decides which edges are recurrent
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SavedAgent {
+    pub version: u32,
+    pub color: Color,
+    pub locomotion: Locomotion,
+    pub nodes: Vec<SavedNode>,
+    pub connections: Vec<ConnectionGene>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SavedNode {
+    pub id: u64,
+    #[serde(flatten)]
+    pub parameters: NodeParameters,
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(io::Error),
+    Format(serde_json::Error),
+    Version(u32, u32), //the file has the first format version but this build reads the second
+    UnknownNode(u64),  //a connection uses a node id that isn't in the file
+    DuplicateNode(u64), //two nodes in the file have the same id
+    Invalid(String),   //the file is well formed but doesn't describe a valid world
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SaveError::Io(error) => write!(f, "{}", error),
+            SaveError::Format(error) => write!(f, "invalid file: {}", error),
+            SaveError::Version(found, expected) => write!(
+                f,
+                "file has format version {} but only version {} can be read",
+                found, expected
+            ),
+            SaveError::UnknownNode(id) => write!(f, "connection uses unknown node id {}", id),
+            SaveError::DuplicateNode(id) => write!(f, "node id {} is used twice", id),
+            SaveError::Invalid(reason) => write!(f, "invalid file: {}", reason),
+        }
+    }
+}
+
+impl From<io::Error> for SaveError {
+    fn from(error: io::Error) -> SaveError {
+        SaveError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for SaveError {
+    fn from(error: serde_json::Error) -> SaveError {
+        SaveError::Format(error)
+    }
+}
+
+impl SavedAgent {
+    pub fn new(agent: &Agent) -> SavedAgent {
+        let nodes = agent
+            .brain
+            .iter()
+            .zip(&agent.node_ids)
+            .map(|(node, id)| SavedNode {
+                id: *id,
+                parameters: node.borrow().get_parameters(),
+            })
+            .collect();
+
+        let mut connections = vec![];
+        for (node, id) in agent.brain.iter().zip(&agent.node_ids) {
+            let node = node.borrow();
+            let edges = node.get_inputs().iter().zip(node.get_weights());
+            for ((input, weight), innovation) in edges.zip(node.get_innovations()) {
+                connections.push(ConnectionGene {
+                    innovation: *innovation,
+                    input: agent.node_ids[agent.get_node_index(input)],
+                    output: *id,
+                    weight: *weight,
+                });
+            }
+        }
+
+        SavedAgent {
+            version: GENOME_VERSION,
+            color: agent.color,
+            locomotion: agent.locomotion,
+            nodes,
+            connections,
+        }
+    }
+
+    ///builds a newborn agent with this genome keeping its node ids and innovation numbers, which are reserved
+    ///so the world never reuses them, only for genomes from this world like the agents in a snapshot
+    pub fn to_agent(&self, innovations: &mut InnovationRegistry) -> Result<Agent, SaveError> {
+        //the color comes from the file and the heading starts at 0, so loading never uses the world's rng
+        let mut agent = Agent::new(self.locomotion, &mut StepRng::new(0, 0));
+        agent.color = self.color;
+
+        let mut index = HashMap::new();
+        for node in &self.nodes {
+            if index.insert(node.id, agent.brain.len()).is_some() {
+                return Err(SaveError::DuplicateNode(node.id));
+            }
+            agent
+                .brain
+                .push(Rc::new(RefCell::new(node.parameters.build())));
+            agent.node_ids.push(node.id);
+            innovations.reserve_node(node.id);
+        }
+
+        for connection in &self.connections {
+            let input = *index
+                .get(&connection.input)
+                .ok_or(SaveError::UnknownNode(connection.input))?;
+            let output = *index
+                .get(&connection.output)
+                .ok_or(SaveError::UnknownNode(connection.output))?;
+
+            let (input, output) = (&agent.brain[input], &agent.brain[output]);
+            if !output.borrow().accepts_inputs() {
+                return Err(SaveError::Invalid(format!(
+                    "node {} is connected into but can't have inputs",
+                    connection.output
+                )));
+            }
+            input.borrow_mut().add_output(Rc::clone(output));
+            output.borrow_mut().add_input(
+                Rc::clone(input),
+                connection.weight,
+                connection.innovation,
+            );
+            innovations.reserve_connection(
+                connection.input,
+                connection.output,
+                connection.innovation,
+            );
+        }
+
+        agent.compile_brain();
+        Ok(agent)
+    }
+
+    ///builds a newborn agent with this genome for a world it didn't come from, another world can have used the
+    ///same ids for other nodes and connections so every node gets a new id and every connection its number in this world
+    pub fn import(&self, innovations: &mut InnovationRegistry) -> Result<Agent, SaveError> {
+        //checked with a throwaway registry first so a broken file doesn't use up ids
+        self.to_agent(&mut InnovationRegistry::new())?;
+
+        let ids: HashMap<u64, u64> = self
+            .nodes
+            .iter()
+            .map(|node| (node.id, innovations.node()))
+            .collect();
+        let translated = SavedAgent {
+            version: self.version,
+            color: self.color,
+            locomotion: self.locomotion,
+            nodes: self
+                .nodes
+                .iter()
+                .map(|node| SavedNode {
+                    id: ids[&node.id],
+                    parameters: node.parameters,
+                })
+                .collect(),
+            connections: self
+                .connections
+                .iter()
+                .map(|connection| {
+                    let (input, output) = (ids[&connection.input], ids[&connection.output]);
+                    ConnectionGene {
+                        innovation: innovations.connection(input, output),
+                        input,
+                        output,
+                        weight: connection.weight,
+                    }
+                })
+                .collect(),
+        };
+        translated.to_agent(innovations)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("agents can always be written as JSON")
+    }
+
+    pub fn from_json(json: &str) -> Result<SavedAgent, SaveError> {
+        from_versioned_json(json, GENOME_VERSION)
+    }
+}
+
+///reads the version first, so a file from another version gives a version error instead of a confusing format error
+pub fn from_versioned_json<T: DeserializeOwned>(json: &str, expected: u32) -> Result<T, SaveError> {
+    let value: serde_json::Value = serde_json::from_str(json)?;
+    let version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+    if version != expected {
+        return Err(SaveError::Version(version, expected));
+    }
+    Ok(serde_json::from_value(value)?)
+}
+
+///writes a genome to a JSON file, SavedAgent::new gets the genome of a living agent
+pub fn save_agent(genome: &SavedAgent, path: &Path) -> Result<(), SaveError> {
+    fs::write(path, genome.to_json())?;
+    Ok(())
+}
+
+///reads an agent from a JSON file written by save_agent, it gets new ids from the world's registry
+pub fn load_agent(path: &Path, innovations: &mut InnovationRegistry) -> Result<Agent, SaveError> {
+    SavedAgent::from_json(&fs::read_to_string(path)?)?.import(innovations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::crossover;
+    use crate::world::{World, WorldControls};
+    use std::sync::{Arc, Mutex};
+
+    fn world(seed: u64) -> World {
+        let mut config = Config::new();
+        config.seed = Some(seed);
+        let mut world = World::new(Arc::new(Mutex::new(WorldControls::new())), &config);
+        world.add_n_agents(20);
+        world
+    }
+
+    #[test]
+    fn imported_genome_breeds_with_native_agents() {
+        let exported = SavedAgent::new(&world(7).agents[0]).to_json();
+        let mut world = world(8);
+        let agent = SavedAgent::from_json(&exported)
+            .unwrap()
+            .import(&mut world.innovations)
+            .unwrap();
+        world.add_agent(agent);
+
+        //every node id and innovation number means the same thing in every brain of the world
+        let mut connections = HashMap::new();
+        for agent in &world.agents {
+            for gene in crossover::connection_genes(agent) {
+                let ends = connections
+                    .entry(gene.innovation)
+                    .or_insert((gene.input, gene.output));
+                assert!(*ends == (gene.input, gene.output));
+            }
+        }
+
+        let (imported, natives) = world.agents.split_last().unwrap();
+        let (mut innovations, mut rng) = (world.innovations.clone(), world.rng.clone());
+        for native in natives {
+            for (parent, mate) in [(imported, native), (native, imported)] {
+                parent.reproduce(
+                    Some(mate),
+                    world.fitness.as_ref(),
+                    &world.mutation,
+                    &mut innovations,
+                    &mut rng,
+                );
+            }
+        }
+    }
+}
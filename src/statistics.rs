@@ -0,0 +1,175 @@
+use crate::world::World;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+///where and how often the world is sampled, no statistics are written if path isn't set
+///the format is taken from the file extension if it isn't set, .jsonl is JSON Lines and anything else CSV
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default = "StatisticsConfig::new", deny_unknown_fields)]
+pub struct StatisticsConfig {
+    pub path: Option<PathBuf>,
+    pub interval: u64, //frames between samples
+    pub format: Option<StatisticsFormat>,
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize)]
+pub enum StatisticsFormat {
+    Csv,
+    JsonLines,
+}
+
+///the world at one tick, births and deaths are counted since the last sample
+#[derive(Serialize)]
+pub struct Sample {
+    pub tick: u64,
+    pub generation: u64,
+    pub population: usize,
+    pub total_food: f32,
+    pub mean_food: f32, //per cell of the food grid
+    pub mean_nodes: f32,
+    pub max_nodes: usize,
+    pub mean_edges: f32,
+    pub max_edges: usize,
+    pub births: u64,
+    pub deaths: u64,
+    pub mean_energy: f32,
+}
+
+const CSV_HEADER: &str = "tick,generation,population,total_food,mean_food,mean_nodes,max_nodes,mean_edges,max_edges,births,deaths,mean_energy";
+
+///samples the world every interval frames and writes each sample as a line of the file
+pub struct Statistics {
+    interval: u64,
+    format: StatisticsFormat,
+    writer: BufWriter<File>,
+    births: u64, //world.births at the last sample
+    deaths: u64, //world.deaths at the last sample
+}
+
+impl StatisticsConfig {
+    pub fn new() -> StatisticsConfig {
+        StatisticsConfig {
+            path: None,
+            interval: 100,
+            format: None,
+        }
+    }
+}
+
+impl StatisticsFormat {
+    pub fn from_path(path: &Path) -> StatisticsFormat {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("jsonl") => StatisticsFormat::JsonLines,
+            _ => StatisticsFormat::Csv,
+        }
+    }
+}
+
+impl Sample {
+    pub fn new(world: &World, births: u64, deaths: u64) -> Sample {
+        let population = world.agents.len();
+        let nodes: Vec<usize> = world.agents.iter().map(|agent| agent.brain.len()).collect();
+        let edges: Vec<usize> = world
+            .agents
+            .iter()
+            .map(|agent| {
+                agent
+                    .brain
+                    .iter()
+                    .map(|node| node.borrow().get_inputs().len())
+                    .sum()
+            })
+            .collect();
+        let total_food: f32 = world.food.iter().flatten().sum();
+        let cells = world.food.iter().map(|column| column.len()).sum::<usize>();
+        let energy: f32 = world.agents.iter().map(|agent| agent.energy).sum();
+        let mean = |total: f32, count: usize| if count > 0 { total / count as f32 } else { 0.0 };
+
+        Sample {
+            tick: world.tick,
+            generation: world.generation,
+            population,
+            total_food,
+            mean_food: mean(total_food, cells),
+            mean_nodes: mean(nodes.iter().sum::<usize>() as f32, population),
+            max_nodes: nodes.iter().copied().max().unwrap_or(0),
+            mean_edges: mean(edges.iter().sum::<usize>() as f32, population),
+            max_edges: edges.iter().copied().max().unwrap_or(0),
+            births,
+            deaths,
+            mean_energy: mean(energy, population),
+        }
+    }
+
+    fn to_csv(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{}",
+            self.tick,
+            self.generation,
+            self.population,
+            self.total_food,
+            self.mean_food,
+            self.mean_nodes,
+            self.max_nodes,
+            self.mean_edges,
+            self.max_edges,
+            self.births,
+            self.deaths,
+            self.mean_energy
+        )
+    }
+}
+
+impl Statistics {
+    ///creates the file, replacing one that is already there, returns None if no path is set
+    pub fn create(config: &StatisticsConfig, world: &World) -> io::Result<Option<Statistics>> {
+        let Some(path) = &config.path else {
+            return Ok(None);
+        };
+
+        let format = config
+            .format
+            .unwrap_or_else(|| StatisticsFormat::from_path(path));
+        let mut writer = BufWriter::new(File::create(path)?);
+        if let StatisticsFormat::Csv = format {
+            writeln!(writer, "{}", CSV_HEADER)?;
+        }
+        Ok(Some(Statistics {
+            interval: config.interval,
+            format,
+            writer,
+            births: world.births,
+            deaths: world.deaths,
+        }))
+    }
+
+    ///writes a sample if the world is on a sampling tick, call it after every step
+    pub fn record(&mut self, world: &World) -> io::Result<()> {
+        if !world.tick.is_multiple_of(self.interval) {
+            return Ok(());
+        }
+
+        //a loaded snapshot can have counted fewer births and deaths than the world before it
+        let sample = Sample::new(
+            world,
+            world.births.saturating_sub(self.births),
+            world.deaths.saturating_sub(self.deaths),
+        );
+        self.births = world.births;
+        self.deaths = world.deaths;
+
+        match self.format {
+            StatisticsFormat::Csv => writeln!(self.writer, "{}", sample.to_csv())?,
+            StatisticsFormat::JsonLines => {
+                serde_json::to_writer(&mut self.writer, &sample)?;
+                writeln!(self.writer)?;
+            }
+        }
+        //flushed every sample so the file is complete even if the window is closed mid run
+        self.writer.flush()
+    }
+}
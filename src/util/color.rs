@@ -1,4 +1,7 @@
-#[derive(Copy, Clone)]
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Serialize, Deserialize)]
 pub struct Color {
     pub r: f32,
     pub g: f32,
@@ -6,11 +9,20 @@ pub struct Color {
 }
 
 impl Color {
-    pub fn random() -> Color {
+    pub fn random(rng: &mut dyn RngCore) -> Color {
+        Color {
+            r: rng.gen(),
+            g: rng.gen(),
+            b: rng.gen(),
+        }
+    }
+
+    ///returns a copy of the color with each channel shifted by up to amount
+    pub fn drift(&self, amount: f32, rng: &mut dyn RngCore) -> Color {
         Color {
-            r: rand::random(),
-            g: rand::random(),
-            b: rand::random(),
+            r: (self.r + (rng.gen::<f32>() * 2.0 - 1.0) * amount).clamp(0.0, 1.0),
+            g: (self.g + (rng.gen::<f32>() * 2.0 - 1.0) * amount).clamp(0.0, 1.0),
+            b: (self.b + (rng.gen::<f32>() * 2.0 - 1.0) * amount).clamp(0.0, 1.0),
         }
     }
 }
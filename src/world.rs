@@ -1,25 +1,54 @@
-use crate::agent::RenderableAgent;
+pub mod agent_grid;
+pub mod food;
+pub mod generation;
+pub mod geometry;
+pub mod hall_of_fame;
+pub mod innovation;
+pub mod lineage;
+pub mod snapshot;
+pub mod species;
+
+use crate::agent::{Locomotion, RenderableAgent, METABOLISM_COST, REPRODUCTION_ENERGY};
+use crate::config::{BrainConfig, Config};
+use crate::fitness::{Fitness, FitnessConfig};
+use crate::mutation::MutationConfig;
+use crate::save::SavedAgent;
+use agent_grid::AgentGrid;
+use food::FoodGrowth;
+use generation::Evolution;
+use geometry::Geometry;
+use hall_of_fame::HallOfFame;
+use innovation::InnovationRegistry;
+use lineage::Lineage;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
+use species::{SpeciationConfig, Species};
 use std::{
-    cell::RefCell,
-    rc::Rc,
+    mem,
     sync::{Arc, Mutex},
-    thread,
-    time::Duration,
 };
 
 use super::Agent;
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Serialize, Deserialize)]
 pub enum GameSpeed {
     Slow,
     Medium,
     Fast,
 }
+#[derive(Serialize, Deserialize)]
 pub struct WorldControls {
     pub paused: bool,
     pub started: bool,
     pub step: bool,
     pub speed: GameSpeed,
+    #[serde(skip)]
+    pub tick_rate: f64, //ticks per second the game thread measured, 0 while paused
+    #[serde(skip)]
+    pub save: bool, //asks the game thread to write a snapshot
+    #[serde(skip)]
+    pub load: bool, //asks the game thread to read the snapshot back
 }
 impl WorldControls {
     pub fn new() -> WorldControls {
@@ -28,110 +57,314 @@ impl WorldControls {
             started: false,
             step: false,
             speed: GameSpeed::Slow,
+            tick_rate: 0.0,
+            save: false,
+            load: false,
         }
     }
 }
 pub struct World {
     pub controls: Arc<Mutex<WorldControls>>,
-    pub agents: Vec<Rc<RefCell<Agent>>>,
-    pub width: u32,
-    pub height: u32,
+    pub tick: u64,   //frames simulated so far
+    pub births: u64, //children born over the whole run
+    pub deaths: u64, //agents that died over the whole run, or were replaced at the end of an epoch
+    pub agents: Vec<Agent>,
+    pub agent_grid: AgentGrid, //where every agent was at the start of the frame, for vision
+    pub geometry: Geometry,    //size and edges of the world
     pub food: Vec<Vec<f32>>,
+    pub food_capacity: Vec<Vec<f32>>, //most food each cell can regrow to
+    pub food_growth: FoodGrowth,      //how food regrows and spreads each frame
+    pub eat_rate: f32,                //most food an EatNode can eat in one frame
+    pub brain: BrainConfig,           //size of the random brains of agents added by add_n_agents
+    pub mutation: MutationConfig,     //how children differ from their parent
+    pub seed: u64,                    //seed the world was made with
+    pub rng: ChaCha12Rng,             //every random number in the simulation, seeded from seed
+    pub innovations: InnovationRegistry, //node ids and connection innovation numbers for every brain
+    pub speciation: SpeciationConfig,    //how agents are grouped into species
+    pub species: Vec<Species>,           //species alive at the last assignment
+    next_species: u64,                   //id the next new species gets
+    pub locomotion: Locomotion,          //which effector nodes new agents are built with
+    pub evolution: Evolution,            //steady-state or generational evolution
+    pub generation: u64,                 //epochs finished in generational mode
+    epoch_start: u64,                    //tick the current epoch started on
+    finished: Vec<Agent>,                //agents that died this epoch, kept to be scored
+    pub fitness_config: FitnessConfig,   //the fitness function as data, so it can be saved
+    pub fitness: Box<dyn Fitness>, //how good an agent is, used by selection and the hall of fame
+    pub hall_of_fame: HallOfFame,  //best agents of the run that were scored, see best_agents
+    pub lineage: Lineage,          //ancestry of every agent of the run
 }
 
 pub struct RenderableWorld {
     pub controls: Arc<Mutex<WorldControls>>,
     pub agents: Vec<RenderableAgent>,
-    pub width: u32,
-    pub height: u32,
+    pub geometry: Geometry,
     pub food: Vec<Vec<f32>>,
 }
 
 impl World {
-    pub fn new(controls: Arc<Mutex<WorldControls>>) -> World {
+    pub fn new(controls: Arc<Mutex<WorldControls>>, config: &Config) -> World {
+        let seed = config.seed.unwrap_or_else(rand::random);
+        let mut rng = ChaCha12Rng::seed_from_u64(seed);
+        let geometry = Geometry::new(
+            config.world.width,
+            config.world.height,
+            config.world.boundary,
+        );
+        let food_capacity: Vec<Vec<f32>> = (0..geometry.width)
+            .map(|_| (0..geometry.height).map(|_| rng.gen::<f32>()).collect())
+            .collect(); //random carrying capacity for each cell
+
         World {
             controls,
+            tick: 0,
+            births: 0,
+            deaths: 0,
             agents: Vec::new(),
-            width: 25,
-            height: 25,
-            food: (0..100)
-                .map(|_| (0..65).map(|_| rand::random::<f32>()).collect())
-                .collect(), //random food grid
+            agent_grid: AgentGrid::new(),
+            geometry,
+            food: food_capacity.clone(), //every cell starts full
+            food_capacity,
+            food_growth: config.food,
+            eat_rate: config.world.eat_rate,
+            brain: config.brain,
+            mutation: config.mutation,
+            seed,
+            rng,
+            innovations: InnovationRegistry::new(),
+            speciation: config.speciation,
+            species: Vec::new(),
+            next_species: 0,
+            locomotion: config.locomotion,
+            evolution: config.evolution,
+            generation: 0,
+            epoch_start: 0,
+            finished: Vec::new(),
+            fitness_config: config.fitness.clone(),
+            fitness: config.fitness.build(),
+            hall_of_fame: HallOfFame::new(config.hall_of_fame),
+            lineage: Lineage::new(),
         }
     }
 
     pub fn renderable_clone(&self) -> RenderableWorld {
         let mut renderable_agents = vec![];
         for agent in &self.agents {
-            renderable_agents.push(Agent::renderable_clone(&agent.borrow()));
+            renderable_agents.push(agent.renderable_clone());
         }
         RenderableWorld {
             controls: Arc::clone(&self.controls),
             agents: renderable_agents,
-            width: self.width,
-            height: self.height,
+            geometry: self.geometry,
             food: self.food.clone(),
         }
     }
 
+    ///seeds the world with n agents that have random brains, later generations come from reproduction
+    ///in generational mode this is also how generation zero is made if no agents were added before the first frame
     pub fn add_n_agents(&mut self, n: usize) {
+        let first = self.agents.len();
         for _ in 0..n {
-            let agent = Rc::new(RefCell::new(Agent::new()));
+            let mut agent = Agent::new(self.locomotion, &mut self.rng);
 
-            //TODO: change the default agent brain, and let you customize this
-            for _ in 0..15 {
-                agent.borrow_mut().add_random_node();
+            for _ in 0..self.brain.nodes {
+                agent.add_random_seed_node(&mut self.innovations, &mut self.rng);
             }
 
-            for _ in 0..25 {
-                agent.borrow_mut().connect_random_nodes();
+            for _ in 0..self.brain.connections {
+                agent.connect_random_nodes(&mut self.innovations, &mut self.rng);
             }
 
+            agent.compile_brain();
             self.agents.push(agent);
         }
+        self.assign_species();
+
+        //recorded after speciation so the lineage has the species they start in
+        for agent in &mut self.agents[first..] {
+            self.lineage.birth(agent, self.tick);
+        }
+    }
+
+    ///the hall of fame with the agents that haven't been scored yet considered too, read this rather than hall_of_fame
+    ///agents only go into the stored hall of fame when they die or their epoch ends, so the best ones can still be alive
+    pub fn best_agents(&self) -> HallOfFame {
+        let mut best = self.hall_of_fame.clone();
+        for agent in self.agents.iter().chain(&self.finished) {
+            best.consider(agent, self.fitness.as_ref(), self.tick);
+        }
+        best
+    }
+
+    ///adds an agent made outside the world, like one loaded with save::load_agent, as a newborn with no parents
+    pub fn add_agent(&mut self, mut agent: Agent) {
+        agent.parent = None;
+        agent.mate = None;
+        self.agents.push(agent);
+        self.assign_species();
+
+        let agent = self.agents.last_mut().unwrap();
+        self.lineage.birth(agent, self.tick);
     }
 
-    pub fn simulate_frame(world: Rc<RefCell<World>>) {
-        //skip if paused
-        if world.borrow().controls.lock().unwrap().paused
-            && !world.borrow().controls.lock().unwrap().step
+    ///the genome of a living agent, one that died this epoch or one in the hall of fame
+    pub fn genome(&self, id: u64) -> Option<SavedAgent> {
+        if let Some(agent) = self
+            .agents
+            .iter()
+            .chain(&self.finished)
+            .find(|agent| agent.id == id)
         {
+            return Some(SavedAgent::new(agent));
+        }
+        self.hall_of_fame
+            .entries
+            .iter()
+            .find(|entry| entry.id == id)
+            .map(|entry| entry.genome.clone())
+    }
+
+    ///sorts every agent into a species, this runs every speciation.interval frames
+    pub fn assign_species(&mut self) {
+        species::assign(
+            &mut self.species,
+            &mut self.agents,
+            &self.speciation,
+            &mut self.next_species,
+        );
+    }
+
+    ///scores every agent of the epoch and replaces them with the next generation, the food is reset too
+    fn next_generation(&mut self) {
+        let Evolution::Generational(config) = self.evolution else {
             return;
+        };
+
+        //agents still alive at the end of the epoch die to make room for the next generation
+        self.deaths += self.agents.len() as u64;
+        for agent in &mut self.agents {
+            self.lineage.death(agent, self.tick);
+        }
+        let mut scored = mem::take(&mut self.agents);
+        scored.append(&mut self.finished);
+        for agent in &scored {
+            self.hall_of_fame
+                .consider(agent, self.fitness.as_ref(), self.tick);
         }
 
-        //use for stepping one frame at a time
-        if world.borrow().controls.lock().unwrap().step {
-            world.borrow().controls.lock().unwrap().step = false;
+        if scored.is_empty() {
+            self.add_n_agents(config.population);
         } else {
-            //control speed of slow, medium, fast
-            let mut sleep_time = None;
-            match world.borrow().controls.lock().unwrap().speed {
-                GameSpeed::Slow => sleep_time = Some(Duration::from_millis(400)),
-                GameSpeed::Medium => sleep_time = Some(Duration::from_millis(33)),
-                GameSpeed::Fast => {}
+            self.agents = generation::breed(
+                &scored,
+                &config,
+                self.fitness.as_ref(),
+                &self.mutation,
+                &mut self.innovations,
+                &mut self.rng,
+            );
+            self.births += self.agents.len() as u64;
+            for agent in &mut self.agents {
+                self.lineage.birth(agent, self.tick);
+            }
+        }
+
+        self.food = self.food_capacity.clone();
+        self.generation += 1;
+        self.epoch_start = self.tick;
+    }
+
+    ///simulates one frame, this never sleeps or checks the controls, the loop calling it handles pausing and timing
+    pub fn step(&mut self) {
+        //generation zero falls back to random brains
+        if let Evolution::Generational(config) = self.evolution {
+            if self.generation == 0 && self.tick == 0 && self.agents.is_empty() {
+                self.add_n_agents(config.population);
             }
-            if let Some(duration) = sleep_time {
-                thread::sleep(duration);
+        }
+
+        self.agent_grid.build(&self.agents, &self.geometry);
+
+        //evaluate every brain, the plan is taken out of the agent so nodes can change the agent and the world
+        for i in 0..self.agents.len() {
+            let mut plan = mem::take(&mut self.agents[i].plan);
+            plan.evaluate(i, self);
+            self.agents[i].plan = plan;
+        }
+
+        //being alive costs energy
+        for agent in &mut self.agents {
+            agent.energy -= METABOLISM_COST;
+            agent.age += 1;
+        }
+
+        //regrow the food
+        food::grow(
+            &mut self.food,
+            &self.food_capacity,
+            &self.food_growth,
+            &self.geometry,
+        );
+
+        //agents with enough energy reproduce, sometimes with a random other agent as a mate
+        //in generational mode the population only changes at the end of an epoch
+        let mut children = vec![];
+        let steady_state = matches!(self.evolution, Evolution::SteadyState);
+        for i in (0..self.agents.len()).filter(|_| steady_state) {
+            if self.agents[i].energy < REPRODUCTION_ENERGY {
+                continue;
+            }
+
+            let mut mate = None;
+            if self.agents.len() > 1 && self.rng.gen::<f32>() < self.mutation.crossover_chance {
+                let j = self.rng.gen_range(0..self.agents.len() - 1);
+                mate = Some(if j >= i { j + 1 } else { j });
+            }
+
+            children.push(self.agents[i].reproduce(
+                mate.map(|j| &self.agents[j]),
+                self.fitness.as_ref(),
+                &self.mutation,
+                &mut self.innovations,
+                &mut self.rng,
+            ));
+            self.agents[i].energy /= 2.0;
+            self.agents[i].offspring += 1;
+            if let Some(j) = mate {
+                self.agents[j].offspring += 1;
             }
         }
+        self.births += children.len() as u64;
+        for child in &mut children {
+            self.lineage.birth(child, self.tick);
+        }
+        self.agents.extend(children);
 
-        for agent in &world.borrow().agents {
-            for node in &agent.borrow().brain {
-                if node.borrow().get_output().is_some() {
-                    continue;
-                }
-                unsafe {
-                    let node_mut: &mut _ = &mut *node.as_ptr();
-                    node_mut.calculate_output(Rc::clone(&agent), Rc::clone(&world));
-                }
+        //remove agents that ran out of energy, in generational mode they are kept to be scored at the end of the epoch
+        let (mut dead, alive): (Vec<Agent>, Vec<Agent>) = mem::take(&mut self.agents)
+            .into_iter()
+            .partition(|agent| agent.is_dead());
+        self.agents = alive;
+        self.deaths += dead.len() as u64;
+        for agent in &mut dead {
+            self.lineage.death(agent, self.tick);
+        }
+        if steady_state {
+            for agent in &dead {
+                self.hall_of_fame
+                    .consider(agent, self.fitness.as_ref(), self.tick);
             }
+        } else {
+            self.finished.extend(dead);
         }
 
-        //reset all nodes some values
-        for agent in &world.borrow().agents {
-            for node in &agent.borrow().brain {
-                node.borrow_mut().reset_output();
+        self.tick += 1;
+        if let Evolution::Generational(config) = self.evolution {
+            if self.tick - self.epoch_start >= config.ticks || self.agents.is_empty() {
+                self.next_generation();
             }
         }
+        if self.tick.is_multiple_of(self.speciation.interval) {
+            self.assign_species();
+        }
     }
 }
@@ -0,0 +1,58 @@
+use super::geometry::Geometry;
+use crate::agent::Agent;
+
+///an agent's index in world.agents and where it was when the grid was built
+pub struct GridAgent {
+    pub index: usize,
+    pub x: f32,
+    pub y: f32,
+}
+
+///agents bucketed by the food grid cell they are in, rebuilt at the start of every frame
+///so vision only has to look at the agents near a point instead of every agent in the world
+pub struct AgentGrid {
+    height: usize,
+    cells: Vec<Vec<GridAgent>>, //column major like the food grid
+}
+
+impl AgentGrid {
+    pub fn new() -> AgentGrid {
+        AgentGrid {
+            height: 0,
+            cells: Vec::new(),
+        }
+    }
+
+    ///puts every agent in the cell it is in now, the cells are reused between frames
+    pub fn build(&mut self, agents: &[Agent], geometry: &Geometry) {
+        self.height = geometry.height as usize;
+        let size = geometry.width as usize * self.height;
+        self.cells.resize_with(size, Vec::new);
+        self.cells.truncate(size);
+        for cell in &mut self.cells {
+            cell.clear();
+        }
+
+        for (index, agent) in agents.iter().enumerate() {
+            let (i, j) = geometry.cell(agent.x, agent.y);
+            self.cells[i * self.height + j].push(GridAgent {
+                index,
+                x: agent.x,
+                y: agent.y,
+            });
+        }
+    }
+
+    ///agents in cell (i, j) and the cells around it, which is every agent within one unit of a point in the cell
+    pub fn near<'a>(
+        &'a self,
+        i: usize,
+        j: usize,
+        geometry: &'a Geometry,
+    ) -> impl Iterator<Item = &'a GridAgent> + 'a {
+        (-1..=1)
+            .flat_map(move |di| (-1..=1).map(move |dj| (di, dj)))
+            .filter_map(move |(di, dj)| geometry.neighbour(i, j, di, dj))
+            .flat_map(move |(i, j)| &self.cells[i * self.height + j])
+    }
+}
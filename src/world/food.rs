@@ -0,0 +1,61 @@
+use super::geometry::Geometry;
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Serialize, Deserialize)]
+#[serde(default = "FoodGrowth::new", deny_unknown_fields)]
+pub struct FoodGrowth {
+    pub regrowth_rate: f32, //logistic growth rate applied to every cell each frame
+    pub diffusion_rate: f32, //how much food spreads to neighbouring cells each frame, 0 turns diffusion off
+    pub seed: f32, //food an empty cell grows from, otherwise an emptied cell could only recover through diffusion
+}
+
+impl FoodGrowth {
+    pub fn new() -> FoodGrowth {
+        FoodGrowth {
+            regrowth_rate: 0.002,
+            diffusion_rate: 0.05,
+            seed: 0.01,
+        }
+    }
+}
+
+///grows every cell logistically toward its carrying capacity, then diffuses food between neighbouring cells
+pub fn grow(
+    food: &mut Vec<Vec<f32>>,
+    capacity: &[Vec<f32>],
+    growth: &FoodGrowth,
+    geometry: &Geometry,
+) {
+    for (column, capacity_column) in food.iter_mut().zip(capacity) {
+        for (cell, capacity) in column.iter_mut().zip(capacity_column) {
+            if *capacity <= 0.0 {
+                *cell = 0.0;
+                continue;
+            }
+            let amount = cell.max(growth.seed.min(*capacity));
+            *cell += growth.regrowth_rate * amount * (1.0 - *cell / capacity);
+            *cell = cell.max(0.0);
+        }
+    }
+
+    if growth.diffusion_rate > 0.0 {
+        diffuse(food, growth.diffusion_rate, geometry);
+    }
+}
+
+///moves food from richer cells to poorer neighbouring cells, food only crosses the edges of a wrapping world
+fn diffuse(food: &mut Vec<Vec<f32>>, rate: f32, geometry: &Geometry) {
+    let mut diffused = food.clone();
+    for i in 0..food.len() {
+        for j in 0..food[i].len() {
+            let mut flow = 0.0;
+            for (di, dj) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                if let Some((ni, nj)) = geometry.neighbour(i, j, di, dj) {
+                    flow += food[ni][nj] - food[i][j];
+                }
+            }
+            diffused[i][j] += rate / 4.0 * flow;
+        }
+    }
+    *food = diffused;
+}
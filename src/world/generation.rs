@@ -0,0 +1,134 @@
+use crate::agent::{Agent, STARTING_ENERGY};
+use crate::fitness::Fitness;
+use crate::mutation::MutationConfig;
+use crate::world::innovation::InnovationRegistry;
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+
+///how the world evolves its agents
+#[derive(Copy, Clone, Serialize, Deserialize)]
+pub enum Evolution {
+    SteadyState,                    //agents reproduce and die continuously
+    Generational(GenerationConfig), //the population is replaced every epoch
+}
+
+///how parents are picked from the scored population, scores are higher for fitter agents
+#[derive(Copy, Clone, Serialize, Deserialize)]
+pub enum Selection {
+    Tournament(usize), //the best of this many agents picked at random
+    Roulette,          //chance proportional to score, shifted so the worst agent has none
+    Truncation(f32),   //uniformly from this fraction of the best agents
+    Rank,              //chance proportional to rank, the worst agent has rank 1
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize)]
+#[serde(default = "GenerationConfig::new", deny_unknown_fields)]
+pub struct GenerationConfig {
+    pub ticks: u64,        //frames in an epoch, which ends early if every agent dies
+    pub population: usize, //agents in every generation
+    pub elitism: usize,    //best agents copied unmutated into the next generation
+    pub selection: Selection,
+}
+
+impl GenerationConfig {
+    pub fn new() -> GenerationConfig {
+        GenerationConfig {
+            ticks: 2000,
+            population: 100,
+            elitism: 2,
+            selection: Selection::Tournament(3),
+        }
+    }
+}
+
+impl Selection {
+    ///index of the chosen parent in scores
+    pub fn select(&self, scores: &[f32], rng: &mut dyn RngCore) -> usize {
+        match *self {
+            Selection::Tournament(size) => (0..size.max(1))
+                .map(|_| rng.gen_range(0..scores.len()))
+                .max_by(|a, b| scores[*a].total_cmp(&scores[*b]))
+                .unwrap(),
+            Selection::Roulette => {
+                let worst = scores.iter().copied().fold(f32::INFINITY, f32::min);
+                let weights: Vec<f32> = scores.iter().map(|score| score - worst).collect();
+                spin(&weights, rng)
+            }
+            Selection::Truncation(fraction) => {
+                let ranked = ranked(scores);
+                let kept =
+                    ((scores.len() as f32 * fraction).ceil() as usize).clamp(1, scores.len());
+                ranked[ranked.len() - 1 - rng.gen_range(0..kept)]
+            }
+            Selection::Rank => {
+                let ranked = ranked(scores);
+                let weights: Vec<f32> = (1..=ranked.len()).map(|rank| rank as f32).collect();
+                ranked[spin(&weights, rng)]
+            }
+        }
+    }
+}
+
+///builds the next generation from the scored agents of the last one
+pub fn breed(
+    scored: &[Agent],
+    config: &GenerationConfig,
+    fitness: &dyn Fitness,
+    mutation: &MutationConfig,
+    innovations: &mut InnovationRegistry,
+    rng: &mut dyn RngCore,
+) -> Vec<Agent> {
+    let scores: Vec<f32> = scored.iter().map(|agent| fitness.score(agent)).collect();
+    let mut next = Vec::with_capacity(config.population);
+
+    //the elite are copied as they are
+    for i in ranked(&scores).into_iter().rev().take(config.elitism) {
+        let mut elite = scored[i].offspring(None, fitness, rng);
+        elite.compile_brain();
+        next.push(elite);
+    }
+
+    while next.len() < config.population {
+        let parent = &scored[config.selection.select(&scores, rng)];
+        let mate = if rng.gen::<f32>() < mutation.crossover_chance {
+            Some(&scored[config.selection.select(&scores, rng)])
+        } else {
+            None
+        };
+        next.push(parent.reproduce(mate, fitness, mutation, innovations, rng));
+    }
+
+    //every generation starts from the same place with full energy
+    for agent in &mut next {
+        agent.x = 0.0;
+        agent.y = 0.0;
+        agent.heading = rng.gen::<f32>() * TAU;
+        agent.energy = STARTING_ENERGY;
+    }
+    next
+}
+
+///indices of scores from worst to best
+fn ranked(scores: &[f32]) -> Vec<usize> {
+    let mut ranked: Vec<usize> = (0..scores.len()).collect();
+    ranked.sort_by(|a, b| scores[*a].total_cmp(&scores[*b]));
+    ranked
+}
+
+///index picked with chance proportional to its weight, uniform if every weight is 0
+fn spin(weights: &[f32], rng: &mut dyn RngCore) -> usize {
+    let total: f32 = weights.iter().sum();
+    if total <= 0.0 {
+        return rng.gen_range(0..weights.len());
+    }
+
+    let mut target = rng.gen::<f32>() * total;
+    for (i, weight) in weights.iter().enumerate() {
+        target -= weight;
+        if target < 0.0 {
+            return i;
+        }
+    }
+    weights.len() - 1
+}
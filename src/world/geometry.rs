@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+use std::f32::consts::{PI, TAU};
+
+///what happens to agents that reach the edge of the world
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Boundary {
+    Clamp,   //agents stop at the edge
+    Wrap,    //agents leaving one side come back on the opposite side, the world is a torus
+    Reflect, //agents bounce back off the edge
+}
+
+///size and topology of the world, the food grid has one cell per unit of width and height
+#[derive(Copy, Clone, Serialize, Deserialize)]
+pub struct Geometry {
+    pub width: u32,
+    pub height: u32,
+    pub boundary: Boundary,
+}
+
+impl Geometry {
+    pub fn new(width: u32, height: u32, boundary: Boundary) -> Geometry {
+        Geometry {
+            width,
+            height,
+            boundary,
+        }
+    }
+
+    ///moves a position that may be outside the world back inside it
+    pub fn bound(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            bound_axis(x, self.width as f32, self.boundary),
+            bound_axis(y, self.height as f32, self.boundary),
+        )
+    }
+
+    ///the heading an agent has after bound moves it from (x, y), only reflecting walls change it
+    pub fn reflect_heading(&self, x: f32, y: f32, heading: f32) -> f32 {
+        if self.boundary != Boundary::Reflect {
+            return heading;
+        }
+
+        let mut heading = heading;
+        if x < 0.0 || x > self.width as f32 {
+            heading = PI - heading;
+        }
+        if y < 0.0 || y > self.height as f32 {
+            heading = -heading;
+        }
+        heading.rem_euclid(TAU)
+    }
+
+    ///the food grid cell a position is in
+    pub fn cell(&self, x: f32, y: f32) -> (usize, usize) {
+        let (x, y) = self.bound(x, y);
+        (
+            (x.max(0.0) as usize).min(self.width as usize - 1),
+            (y.max(0.0) as usize).min(self.height as usize - 1),
+        )
+    }
+
+    ///the cell offset by (di, dj) from (i, j), None if it is past the edge of a world that doesn't wrap
+    pub fn neighbour(&self, i: usize, j: usize, di: i32, dj: i32) -> Option<(usize, usize)> {
+        let i = i as i64 + di as i64;
+        let j = j as i64 + dj as i64;
+        let width = self.width as i64;
+        let height = self.height as i64;
+        match self.boundary {
+            Boundary::Wrap => Some((i.rem_euclid(width) as usize, j.rem_euclid(height) as usize)),
+            Boundary::Clamp | Boundary::Reflect => {
+                if i < 0 || j < 0 || i >= width || j >= height {
+                    None
+                } else {
+                    Some((i as usize, j as usize))
+                }
+            }
+        }
+    }
+}
+
+fn bound_axis(value: f32, size: f32, boundary: Boundary) -> f32 {
+    match boundary {
+        Boundary::Clamp => value.clamp(0.0, size),
+        Boundary::Wrap => value.rem_euclid(size),
+        Boundary::Reflect => {
+            //fold the position back and forth over the world until it is inside
+            let folded = value.rem_euclid(2.0 * size);
+            if folded > size {
+                2.0 * size - folded
+            } else {
+                folded
+            }
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use crate::agent::Agent;
+use crate::fitness::Fitness;
+use crate::save::SavedAgent;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub score: f32,
+    pub tick: u64,    //frame the agent was scored on, when it died or its epoch ended
+    pub species: u64, //species the agent was in
+    pub id: u64,      //the agent's id in the world's lineage
+    pub genome: SavedAgent, //the agent's whole genome, so it can be exported after the agent is gone
+}
+
+///the best agents of the whole run ranked by the world's fitness function, best first
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HallOfFame {
+    pub size: usize, //most entries kept
+    pub entries: Vec<Entry>,
+}
+
+impl HallOfFame {
+    pub fn new(size: usize) -> HallOfFame {
+        HallOfFame {
+            size,
+            entries: Vec::new(),
+        }
+    }
+
+    ///adds the agent if it scores better than the worst entry or there is room left
+    pub fn consider(&mut self, agent: &Agent, fitness: &dyn Fitness, tick: u64) {
+        let score = fitness.score(agent);
+        let position = self.entries.partition_point(|entry| entry.score >= score);
+        if position >= self.size {
+            return;
+        }
+
+        self.entries.insert(
+            position,
+            Entry {
+                score,
+                tick,
+                species: agent.species,
+                id: agent.id,
+                genome: SavedAgent::new(agent),
+            },
+        );
+        self.entries.truncate(self.size);
+    }
+}
@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+///hands out node ids and connection innovation numbers for the whole world, so the same structural change
+///gets the same number in every brain it happens in and brains can be lined up gene by gene for crossover
+#[derive(Clone, Serialize, Deserialize)]
+pub struct InnovationRegistry {
+    #[serde(with = "connection_map")]
+    connections: BTreeMap<(u64, u64), u64>, //(input node id, output node id) to innovation number
+    splits: BTreeMap<u64, u64>, //innovation number of a split connection to the id of the node put in it
+    seed_nodes: BTreeMap<String, Vec<u64>>, //node type to the ids of the first, second, ... node of that type in seeded brains
+    next_innovation: u64,
+    next_node: u64,
+}
+
+impl InnovationRegistry {
+    pub fn new() -> InnovationRegistry {
+        InnovationRegistry {
+            connections: BTreeMap::new(),
+            splits: BTreeMap::new(),
+            seed_nodes: BTreeMap::new(),
+            next_innovation: 0,
+            next_node: 0,
+        }
+    }
+
+    ///id for a brand new node
+    pub fn node(&mut self) -> u64 {
+        self.next_node += 1;
+        self.next_node - 1
+    }
+
+    ///id of the k-th node of this type in a seeded brain, the same in every seeded brain
+    pub fn seed_node(&mut self, name: &str, k: usize) -> u64 {
+        let ids = self.seed_nodes.entry(name.to_string()).or_default();
+        while ids.len() <= k {
+            self.next_node += 1;
+            ids.push(self.next_node - 1);
+        }
+        ids[k]
+    }
+
+    ///innovation number of the connection from input to output, new only the first time the pair is connected
+    pub fn connection(&mut self, input: u64, output: u64) -> u64 {
+        let next_innovation = &mut self.next_innovation;
+        *self.connections.entry((input, output)).or_insert_with(|| {
+            *next_innovation += 1;
+            *next_innovation - 1
+        })
+    }
+
+    ///makes sure a node id from a loaded brain is never handed out again
+    pub fn reserve_node(&mut self, id: u64) {
+        self.next_node = self.next_node.max(id + 1);
+    }
+
+    ///remembers a connection from a loaded brain, so connecting the same nodes again gives the same number
+    pub fn reserve_connection(&mut self, input: u64, output: u64, innovation: u64) {
+        self.connections
+            .entry((input, output))
+            .or_insert(innovation);
+        self.next_innovation = self.next_innovation.max(innovation + 1);
+    }
+
+    ///id of the hidden node put in the middle of the connection, the same split always gives the same id
+    pub fn split(&mut self, innovation: u64) -> u64 {
+        let next_node = &mut self.next_node;
+        *self.splits.entry(innovation).or_insert_with(|| {
+            *next_node += 1;
+            *next_node - 1
+        })
+    }
+}
+
+//JSON maps need string keys, so the connections are saved as a list of [input, output, innovation]
+//the maps are ordered so saving the same registry twice gives the same file
+mod connection_map {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::collections::BTreeMap;
+
+    pub fn serialize<S: Serializer>(
+        connections: &BTreeMap<(u64, u64), u64>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(
+            connections
+                .iter()
+                .map(|((input, output), innovation)| (input, output, innovation)),
+        )
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<(u64, u64), u64>, D::Error> {
+        let connections: Vec<(u64, u64, u64)> = Vec::deserialize(deserializer)?;
+        Ok(connections
+            .into_iter()
+            .map(|(input, output, innovation)| ((input, output), innovation))
+            .collect())
+    }
+}
@@ -0,0 +1,292 @@
+use crate::agent::Agent;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write, fs, io, path::Path};
+
+///what is remembered about every agent that was ever alive, kept small since nothing is ever removed
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Record {
+    pub parent: Option<u64>, //the parent the agent got its brain structure from, None for agents the world was seeded with
+    pub mate: Option<u64>,   //the other parent of a crossover
+    pub birth: u64,          //tick the agent was born on
+    pub death: Option<u64>,  //tick the agent died on, None while it is alive
+    pub species: u64,        //species the agent was born into
+}
+
+///ancestry of every agent of the run including extinct lineages, agent ids are indices into records
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Lineage {
+    records: Vec<Record>,
+}
+
+#[derive(Serialize)]
+struct ExportedAgent {
+    id: u64,
+    #[serde(flatten)]
+    record: Record,
+    depth: u64, //generations to the seeded ancestor, following the parent of each agent
+}
+
+#[derive(Serialize)]
+struct Edge {
+    parent: u64,
+    child: u64,
+    mate: bool, //the edge is from the mate of a crossover rather than the parent
+}
+
+#[derive(Serialize)]
+struct EdgeList {
+    agents: Vec<ExportedAgent>,
+    edges: Vec<Edge>,
+}
+
+impl Lineage {
+    pub fn new() -> Lineage {
+        Lineage {
+            records: Vec::new(),
+        }
+    }
+
+    ///gives a newborn agent its id and birth tick and records it, its parent and mate are already set
+    pub fn birth(&mut self, agent: &mut Agent, tick: u64) {
+        agent.id = self.records.len() as u64;
+        agent.birth = tick;
+        self.records.push(Record {
+            parent: agent.parent,
+            mate: agent.mate,
+            birth: tick,
+            death: None,
+            species: agent.species,
+        });
+    }
+
+    pub fn death(&mut self, agent: &mut Agent, tick: u64) {
+        agent.death = Some(tick);
+        if let Some(record) = self.records.get_mut(agent.id as usize) {
+            record.death = Some(tick);
+        }
+    }
+
+    pub fn get(&self, id: u64) -> Option<&Record> {
+        self.records.get(id as usize)
+    }
+
+    ///every parent and mate has a lower id than its child, which the exporters rely on
+    pub fn is_valid(&self) -> bool {
+        self.records.iter().enumerate().all(|(id, record)| {
+            [record.parent, record.mate]
+                .iter()
+                .flatten()
+                .all(|parent| (*parent as usize) < id)
+        })
+    }
+
+    ///most recent agent every one of the agents descends from through their parents, None if they come from different seeded agents
+    pub fn common_ancestor(&self, ids: impl IntoIterator<Item = u64>) -> Option<u64> {
+        let mut ids = ids.into_iter();
+        let mut ancestor = ids.next()?;
+        for mut id in ids {
+            //a parent always has a lower id than its child, so the higher id can't be the ancestor of the other
+            while id != ancestor {
+                if id > ancestor {
+                    id = self.get(id)?.parent?;
+                } else {
+                    ancestor = self.get(ancestor)?.parent?;
+                }
+            }
+        }
+        Some(ancestor)
+    }
+
+    ///generations from the seeded agent the agent descends from through its parents
+    pub fn depth(&self, id: u64) -> u64 {
+        let mut depth = 0;
+        let mut current = self.get(id).and_then(|record| record.parent);
+        while let Some(parent) = current {
+            depth += 1;
+            current = self.get(parent).and_then(|record| record.parent);
+        }
+        depth
+    }
+
+    ///generations between the agent and the seeded agent it descends from through its parents
+    fn depths(&self) -> Vec<u64> {
+        //parents are always born before their children, so their depth is already known
+        let mut depths: Vec<u64> = Vec::with_capacity(self.records.len());
+        for record in &self.records {
+            let depth = match record.parent {
+                Some(parent) => depths[parent as usize] + 1,
+                None => 0,
+            };
+            depths.push(depth);
+        }
+        depths
+    }
+
+    ///the family tree in Newick format, each agent is labelled with its id and its branch is its age when its child was born
+    ///crossovers make the ancestry a graph, the tree follows each agent's parent and the JSON edge list has the mates too
+    ///the agents the world was seeded with are the children of an unlabelled root
+    pub fn newick(&self) -> String {
+        let mut children: Vec<Vec<u64>> = vec![Vec::new(); self.records.len()];
+        let mut roots = Vec::new();
+        for (id, record) in self.records.iter().enumerate() {
+            match record.parent {
+                Some(parent) => children[parent as usize].push(id as u64),
+                None => roots.push(id as u64),
+            }
+        }
+
+        //lineages can be thousands of generations deep, so this walks the tree with a stack instead of recursion
+        enum Step {
+            Open(u64),  //writes the agent, or opens its list of children
+            Close(u64), //closes the list of children and writes the agent
+            Comma,
+        }
+        let mut newick = String::from("(");
+        let mut stack: Vec<Step> = Vec::new();
+        for (i, root) in roots.iter().enumerate().rev() {
+            stack.push(Step::Open(*root));
+            if i > 0 {
+                stack.push(Step::Comma);
+            }
+        }
+        while let Some(step) = stack.pop() {
+            match step {
+                Step::Open(id) if children[id as usize].is_empty() => self.label(&mut newick, id),
+                Step::Open(id) => {
+                    newick.push('(');
+                    stack.push(Step::Close(id));
+                    for (i, child) in children[id as usize].iter().enumerate().rev() {
+                        stack.push(Step::Open(*child));
+                        if i > 0 {
+                            stack.push(Step::Comma);
+                        }
+                    }
+                }
+                Step::Close(id) => {
+                    newick.push(')');
+                    self.label(&mut newick, id);
+                }
+                Step::Comma => newick.push(','),
+            }
+        }
+        newick.push_str(");");
+        newick
+    }
+
+    fn label(&self, newick: &mut String, id: u64) {
+        let record = &self.records[id as usize];
+        let length = match record.parent {
+            Some(parent) => record
+                .birth
+                .saturating_sub(self.records[parent as usize].birth),
+            None => record.birth,
+        };
+        write!(newick, "{}:{}", id, length).unwrap();
+    }
+
+    ///every agent and every parent to child edge as JSON, an agent with a mate has an edge from each parent
+    pub fn edge_list(&self) -> String {
+        let depths = self.depths();
+        let mut edges = Vec::new();
+        for (id, record) in self.records.iter().enumerate() {
+            if let Some(parent) = record.parent {
+                edges.push(Edge {
+                    parent,
+                    child: id as u64,
+                    mate: false,
+                });
+            }
+            if let Some(mate) = record.mate {
+                edges.push(Edge {
+                    parent: mate,
+                    child: id as u64,
+                    mate: true,
+                });
+            }
+        }
+
+        let agents = self
+            .records
+            .iter()
+            .zip(depths)
+            .enumerate()
+            .map(|(id, (record, depth))| ExportedAgent {
+                id: id as u64,
+                record: record.clone(),
+                depth,
+            })
+            .collect();
+        serde_json::to_string(&EdgeList { agents, edges })
+            .expect("lineages can always be written as JSON")
+    }
+
+    pub fn write_newick(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.newick())
+    }
+
+    pub fn write_edge_list(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.edge_list())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::Locomotion;
+    use rand::rngs::mock::StepRng;
+
+    fn birth(lineage: &mut Lineage, parent: Option<u64>, mate: Option<u64>, tick: u64) -> u64 {
+        let mut agent = Agent::new(Locomotion::Heading, &mut StepRng::new(0, 0));
+        agent.parent = parent;
+        agent.mate = mate;
+        lineage.birth(&mut agent, tick);
+        agent.id
+    }
+
+    //0 and 1 are seeded, 2 and 4 are children of 0, 3 is a child of 2 with 1 as its mate
+    fn family() -> Lineage {
+        let mut lineage = Lineage::new();
+        birth(&mut lineage, None, None, 0);
+        birth(&mut lineage, None, None, 0);
+        birth(&mut lineage, Some(0), None, 5);
+        birth(&mut lineage, Some(2), Some(1), 8);
+        birth(&mut lineage, Some(0), None, 10);
+        lineage
+    }
+
+    #[test]
+    fn newick_follows_parents() {
+        assert_eq!(family().newick(), "(((3:3)2:5,4:10)0:0,1:0);");
+    }
+
+    #[test]
+    fn edge_list_has_parents_and_mates() {
+        let edge_list: serde_json::Value = serde_json::from_str(&family().edge_list()).unwrap();
+        let edges: Vec<(u64, u64, bool)> = edge_list["edges"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|edge| {
+                (
+                    edge["parent"].as_u64().unwrap(),
+                    edge["child"].as_u64().unwrap(),
+                    edge["mate"].as_bool().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            edges,
+            [(0, 2, false), (2, 3, false), (1, 3, true), (0, 4, false)]
+        );
+        assert_eq!(edge_list["agents"][3]["depth"], 2);
+    }
+
+    #[test]
+    fn common_ancestor_and_depth() {
+        let lineage = family();
+        assert_eq!(lineage.common_ancestor([3, 4]), Some(0));
+        assert_eq!(lineage.common_ancestor([3, 2]), Some(2));
+        assert_eq!(lineage.common_ancestor([3, 1]), None);
+        assert_eq!(lineage.depth(3), 2);
+    }
+}
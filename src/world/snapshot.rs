@@ -0,0 +1,337 @@
+use super::generation::Evolution;
+use super::hall_of_fame::HallOfFame;
+use super::innovation::InnovationRegistry;
+use super::lineage::Lineage;
+use super::species::{SpeciationConfig, Species};
+use super::{food::FoodGrowth, geometry::Geometry, World, WorldControls};
+use crate::agent::{Agent, Locomotion};
+use crate::config::BrainConfig;
+use crate::fitness::FitnessConfig;
+use crate::mutation::MutationConfig;
+use crate::save::{self, SaveError, SavedAgent};
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+//version 1: the first format
+//version 2: one rng for the whole world replaced the mutation rng, and the seed is saved
+//version 3: the eat rate and starting brain size from the config are saved
+//version 4: births and deaths are counted
+//version 5: agents have ids and the lineage of the run is saved
+//version 6: hall of fame entries keep the agent's id and whole genome
+//version 7: the innovation registry keeps the node ids shared by seeded brains
+//version 8: the fitness function is saved
+pub const SNAPSHOT_VERSION: u32 = 8; //bumped whenever the format changes in a way old files can't be read
+
+///an agent in a snapshot, its genome plus everything that changes while it is alive
+#[derive(Serialize, Deserialize)]
+pub struct SavedWorldAgent {
+    pub genome: SavedAgent,
+    pub x: f32,
+    pub y: f32,
+    pub energy: f32,
+    pub heading: f32,
+    pub age: u32,
+    pub food_eaten: f32,
+    pub distance_travelled: f32,
+    pub offspring: u32,
+    pub species: u64,
+    pub id: u64,
+    pub parent: Option<u64>,
+    pub mate: Option<u64>,
+    pub birth: u64,
+    pub death: Option<u64>,
+    #[serde(with = "floats")]
+    pub values: Vec<f32>, //each node's output last frame in brain order, recurrent edges read these next frame
+    #[serde(with = "floats")]
+    pub states: Vec<f32>, //state each node keeps between frames in brain order
+}
+
+///the whole simulation, loading it resumes the run from the frame it was saved on
+#[derive(Serialize, Deserialize)]
+pub struct SavedWorld {
+    pub version: u32,
+    pub tick: u64,
+    pub births: u64,
+    pub deaths: u64,
+    pub controls: WorldControls,
+    pub geometry: Geometry,
+    pub food: Vec<Vec<f32>>,
+    pub food_capacity: Vec<Vec<f32>>,
+    pub food_growth: FoodGrowth,
+    pub eat_rate: f32,
+    pub brain: BrainConfig,
+    pub mutation: MutationConfig,
+    pub seed: u64,
+    pub rng: ChaCha12Rng,
+    pub innovations: InnovationRegistry,
+    pub speciation: SpeciationConfig,
+    pub species: Vec<Species>,
+    pub next_species: u64,
+    pub locomotion: Locomotion,
+    pub evolution: Evolution,
+    pub generation: u64,
+    pub epoch_start: u64,
+    pub agents: Vec<SavedWorldAgent>,
+    pub finished: Vec<SavedWorldAgent>,
+    pub fitness: FitnessConfig,
+    pub hall_of_fame: HallOfFame,
+    pub lineage: Lineage,
+}
+
+impl SavedWorldAgent {
+    pub fn new(agent: &Agent) -> SavedWorldAgent {
+        SavedWorldAgent {
+            genome: SavedAgent::new(agent),
+            x: agent.x,
+            y: agent.y,
+            energy: agent.energy,
+            heading: agent.heading,
+            age: agent.age,
+            food_eaten: agent.food_eaten,
+            distance_travelled: agent.distance_travelled,
+            offspring: agent.offspring,
+            species: agent.species,
+            id: agent.id,
+            parent: agent.parent,
+            mate: agent.mate,
+            birth: agent.birth,
+            death: agent.death,
+            values: agent.plan.get_values().to_vec(),
+            states: agent.plan.get_states(),
+        }
+    }
+
+    pub fn to_agent(&self, innovations: &mut InnovationRegistry) -> Result<Agent, SaveError> {
+        let mut agent = self.genome.to_agent(innovations)?;
+        if self.values.len() != agent.brain.len() || self.states.len() != agent.brain.len() {
+            return Err(SaveError::Invalid(
+                "an agent has a different number of values or states than nodes".to_string(),
+            ));
+        }
+
+        agent.x = self.x;
+        agent.y = self.y;
+        agent.energy = self.energy;
+        agent.heading = self.heading;
+        agent.age = self.age;
+        agent.food_eaten = self.food_eaten;
+        agent.distance_travelled = self.distance_travelled;
+        agent.offspring = self.offspring;
+        agent.species = self.species;
+        agent.id = self.id;
+        agent.parent = self.parent;
+        agent.mate = self.mate;
+        agent.birth = self.birth;
+        agent.death = self.death;
+        agent.plan.set_values(&self.values);
+        agent.plan.set_states(&self.states);
+        Ok(agent)
+    }
+}
+
+impl World {
+    pub fn snapshot(&self) -> SavedWorld {
+        let controls = self.controls.lock().unwrap();
+        SavedWorld {
+            version: SNAPSHOT_VERSION,
+            tick: self.tick,
+            births: self.births,
+            deaths: self.deaths,
+            controls: WorldControls {
+                paused: controls.paused,
+                started: controls.started,
+                step: controls.step,
+                speed: controls.speed,
+                tick_rate: 0.0,
+                save: false,
+                load: false,
+            },
+            geometry: self.geometry,
+            food: self.food.clone(),
+            food_capacity: self.food_capacity.clone(),
+            food_growth: self.food_growth,
+            eat_rate: self.eat_rate,
+            brain: self.brain,
+            mutation: self.mutation,
+            seed: self.seed,
+            rng: self.rng.clone(),
+            innovations: self.innovations.clone(),
+            speciation: self.speciation,
+            species: self.species.clone(),
+            next_species: self.next_species,
+            locomotion: self.locomotion,
+            evolution: self.evolution,
+            generation: self.generation,
+            epoch_start: self.epoch_start,
+            agents: self.agents.iter().map(SavedWorldAgent::new).collect(),
+            finished: self.finished.iter().map(SavedWorldAgent::new).collect(),
+            fitness: self.fitness_config.clone(),
+            hall_of_fame: self.hall_of_fame.clone(),
+            lineage: self.lineage.clone(),
+        }
+    }
+
+    ///replaces the whole simulation with the snapshot, nothing changes if the snapshot is invalid
+    pub fn restore(&mut self, saved: SavedWorld) -> Result<(), SaveError> {
+        let food_size = |food: &Vec<Vec<f32>>| {
+            food.len() == saved.geometry.width as usize
+                && food
+                    .iter()
+                    .all(|column| column.len() == saved.geometry.height as usize)
+        };
+        if !food_size(&saved.food) || !food_size(&saved.food_capacity) {
+            return Err(SaveError::Invalid(
+                "the food grid doesn't match the world size".to_string(),
+            ));
+        }
+        if !saved.lineage.is_valid() {
+            return Err(SaveError::Invalid(
+                "the lineage has an agent born before its parent".to_string(),
+            ));
+        }
+
+        let mut innovations = saved.innovations;
+        let agents = saved
+            .agents
+            .iter()
+            .map(|agent| agent.to_agent(&mut innovations))
+            .collect::<Result<Vec<Agent>, SaveError>>()?;
+        let finished = saved
+            .finished
+            .iter()
+            .map(|agent| agent.to_agent(&mut innovations))
+            .collect::<Result<Vec<Agent>, SaveError>>()?;
+
+        *self.controls.lock().unwrap() = saved.controls;
+        self.tick = saved.tick;
+        self.births = saved.births;
+        self.deaths = saved.deaths;
+        self.agents = agents;
+        self.geometry = saved.geometry;
+        self.food = saved.food;
+        self.food_capacity = saved.food_capacity;
+        self.food_growth = saved.food_growth;
+        self.eat_rate = saved.eat_rate;
+        self.brain = saved.brain;
+        self.mutation = saved.mutation;
+        self.seed = saved.seed;
+        self.rng = saved.rng;
+        self.innovations = innovations;
+        self.speciation = saved.speciation;
+        self.species = saved.species;
+        self.next_species = saved.next_species;
+        self.locomotion = saved.locomotion;
+        self.evolution = saved.evolution;
+        self.generation = saved.generation;
+        self.epoch_start = saved.epoch_start;
+        self.finished = finished;
+        self.fitness = saved.fitness.build();
+        self.fitness_config = saved.fitness;
+        self.hall_of_fame = saved.hall_of_fame;
+        self.lineage = saved.lineage;
+        Ok(())
+    }
+
+    ///writes a snapshot of the whole simulation to a JSON file
+    pub fn save(&self, path: &Path) -> Result<(), SaveError> {
+        let json = serde_json::to_string(&self.snapshot())?;
+        fs::write(path, json)?;
+        Ok(())
+    }
+
+    ///resumes the simulation from a file written by save
+    pub fn load(&mut self, path: &Path) -> Result<(), SaveError> {
+        let json = fs::read_to_string(path)?;
+        self.restore(save::from_versioned_json(&json, SNAPSHOT_VERSION)?)
+    }
+}
+
+//node outputs can blow up to infinity or NaN, which JSON has no numbers for, so those are saved as strings
+mod floats {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(untagged)]
+    enum Float {
+        Finite(f32),
+        NonFinite(String),
+    }
+
+    pub fn serialize<S: Serializer>(values: &[f32], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(values.iter().map(|value| {
+            if value.is_finite() {
+                Float::Finite(*value)
+            } else {
+                Float::NonFinite(value.to_string())
+            }
+        }))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<f32>, D::Error> {
+        Vec::<Float>::deserialize(deserializer)?
+            .into_iter()
+            .map(|value| match value {
+                Float::Finite(value) => Ok(value),
+                Float::NonFinite(text) => text.parse().map_err(serde::de::Error::custom),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+    use crate::fitness::FitnessConfig;
+    use crate::world::generation::{Evolution, GenerationConfig};
+    use crate::world::{World, WorldControls};
+    use std::sync::{Arc, Mutex};
+
+    fn world(config: &Config) -> World {
+        let mut world = World::new(Arc::new(Mutex::new(WorldControls::new())), config);
+        world.add_n_agents(config.agents);
+        world
+    }
+
+    fn run(world: &mut World, ticks: u64) -> String {
+        for _ in 0..ticks {
+            world.step();
+        }
+        serde_json::to_string(&world.snapshot()).unwrap()
+    }
+
+    fn configs() -> Vec<Config> {
+        let mut steady_state = Config::new();
+        steady_state.seed = Some(7);
+        let mut generational = steady_state.clone();
+        //short epochs so the runs go through a few generations
+        let mut generation = GenerationConfig::new();
+        generation.ticks = 100;
+        generational.evolution = Evolution::Generational(generation);
+        vec![steady_state, generational]
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_run() {
+        for config in configs() {
+            assert!(run(&mut world(&config), 300) == run(&mut world(&config), 300));
+        }
+    }
+
+    #[test]
+    fn resumed_run_matches_an_uninterrupted_one() {
+        for config in configs() {
+            let uninterrupted = run(&mut world(&config), 300);
+
+            let saved = run(&mut world(&config), 150);
+            //the snapshot brings its own fitness function, whatever the world it is loaded into scores by
+            let mut other = config.clone();
+            other.fitness = FitnessConfig::TicksSurvived;
+            let mut resumed = world(&other);
+            resumed
+                .restore(serde_json::from_str(&saved).unwrap())
+                .unwrap();
+            assert!(run(&mut resumed, 150) == uninterrupted);
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use crate::agent::Agent;
+use crate::crossover::{self, Alignment, ConnectionGene};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+///weights of the compatibility distance and how agents are split into species
+#[derive(Copy, Clone, Serialize, Deserialize)]
+#[serde(default = "SpeciationConfig::new", deny_unknown_fields)]
+pub struct SpeciationConfig {
+    pub excess_coefficient: f32,   //weight of excess genes in the distance
+    pub disjoint_coefficient: f32, //weight of disjoint genes in the distance
+    pub weight_coefficient: f32,   //weight of the mean weight difference of matching genes
+    pub node_coefficient: f32,     //weight of the difference in node type counts
+    pub threshold: f32,            //agents closer than this to a representative join its species
+    pub interval: u64,             //frames between reassigning every agent
+}
+
+impl SpeciationConfig {
+    pub fn new() -> SpeciationConfig {
+        SpeciationConfig {
+            excess_coefficient: 1.0,
+            disjoint_coefficient: 1.0,
+            weight_coefficient: 0.4,
+            node_coefficient: 1.0,
+            threshold: 2.5, //random seeded brains are around 2.7 apart, so seeds with similar brains start out together
+            interval: 100,
+        }
+    }
+}
+
+///the parts of a brain the compatibility distance looks at
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Genome {
+    pub genes: Vec<ConnectionGene>,
+    pub node_types: BTreeMap<String, usize>, //how many nodes of each type the brain has
+}
+
+impl Genome {
+    pub fn new(agent: &Agent) -> Genome {
+        let mut node_types = BTreeMap::new();
+        for node in &agent.brain {
+            *node_types
+                .entry(node.borrow().name().to_string())
+                .or_insert(0) += 1;
+        }
+
+        Genome {
+            genes: crossover::connection_genes(agent),
+            node_types,
+        }
+    }
+
+    ///NEAT's compatibility distance plus a term for node types, 0 for identical brains
+    pub fn distance(&self, other: &Genome, config: &SpeciationConfig) -> f32 {
+        let (mut excess, mut disjoint, mut matching, mut weight_difference) = (0, 0, 0, 0.0);
+        for alignment in crossover::align(&self.genes, &other.genes) {
+            match alignment {
+                Alignment::Matching(a, b) => {
+                    matching += 1;
+                    weight_difference += (a.weight - b.weight).abs();
+                }
+                Alignment::Disjoint(..) => disjoint += 1,
+                Alignment::Excess(..) => excess += 1,
+            }
+        }
+        let genes = self.genes.len().max(other.genes.len()).max(1) as f32;
+        let mean_weight_difference = if matching > 0 {
+            weight_difference / matching as f32
+        } else {
+            0.0
+        };
+
+        let mut node_difference = 0;
+        for (name, count) in &self.node_types {
+            node_difference += count.abs_diff(*other.node_types.get(name).unwrap_or(&0));
+        }
+        for (name, count) in &other.node_types {
+            if !self.node_types.contains_key(name) {
+                node_difference += count;
+            }
+        }
+        let nodes = self
+            .node_types
+            .values()
+            .sum::<usize>()
+            .max(other.node_types.values().sum())
+            .max(1) as f32;
+
+        config.excess_coefficient * excess as f32 / genes
+            + config.disjoint_coefficient * disjoint as f32 / genes
+            + config.weight_coefficient * mean_weight_difference
+            + config.node_coefficient * node_difference as f32 / nodes
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Species {
+    pub id: u64,                //never reused, so a species can be followed over a run
+    pub representative: Genome, //agents are compared against this to see if they belong
+    pub size: usize,            //members at the last assignment
+}
+
+///puts every agent in the first species whose representative is within the threshold,
+///agents that fit nowhere start a new species, species left with no members are removed
+pub fn assign(
+    species: &mut Vec<Species>,
+    agents: &mut [Agent],
+    config: &SpeciationConfig,
+    next_id: &mut u64,
+) {
+    for s in species.iter_mut() {
+        s.size = 0;
+    }
+
+    //the first member of each species becomes its new representative, agents are oldest first
+    let mut new_representatives: HashMap<u64, Genome> = HashMap::new();
+    for agent in agents.iter_mut() {
+        let genome = Genome::new(agent);
+        let found = species
+            .iter_mut()
+            .find(|s| genome.distance(&s.representative, config) < config.threshold);
+
+        let s = match found {
+            Some(s) => s,
+            None => {
+                *next_id += 1;
+                species.push(Species {
+                    id: *next_id - 1,
+                    representative: Genome::new(agent),
+                    size: 0,
+                });
+                species.last_mut().unwrap()
+            }
+        };
+
+        s.size += 1;
+        agent.species = s.id;
+        new_representatives.entry(s.id).or_insert(genome);
+    }
+
+    species.retain(|s| s.size > 0);
+    for s in species.iter_mut() {
+        if let Some(genome) = new_representatives.remove(&s.id) {
+            s.representative = genome;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+    use crate::world::{World, WorldControls};
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn seeded_population_forms_few_species() {
+        let mut config = Config::new();
+        config.seed = Some(1);
+        let mut world = World::new(Arc::new(Mutex::new(WorldControls::new())), &config);
+        world.add_n_agents(500);
+
+        assert!(
+            world.species.len() < 100,
+            "500 seeded agents formed {} species",
+            world.species.len()
+        );
+    }
+}